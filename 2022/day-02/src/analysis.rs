@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use crate::{
+    guide::{ColumnIsMove, GuideError, Interpretation, StrategyGuide},
+    rules::{Move, Outcome, Rules},
+};
+
+/// The score when playing the best move against every one of the opponent's moves.
+pub fn max_score(guide: &StrategyGuide, rules: &Rules) -> usize {
+    guide
+        .opponent_moves()
+        .map(|other| {
+            (0..rules.move_count())
+                .map(|myself| rules.score(other, myself))
+                .max()
+                .unwrap_or(0)
+        })
+        .sum()
+}
+
+/// All orders of the moves `0..count`.
+fn permutations(count: usize) -> Vec<Vec<Move>> {
+    if count == 0 {
+        return vec![Vec::new()];
+    }
+
+    permutations(count - 1)
+        .into_iter()
+        .flat_map(|permutation| {
+            (0..count).map(move |position| {
+                let mut extended = permutation.clone();
+                extended.insert(position, count - 1);
+                extended
+            })
+        })
+        .collect()
+}
+
+/// A way of reading the second column as moves: `moves[i]` is the move of the `i`th letter.
+#[derive(Debug, PartialEq)]
+pub struct MappingScore {
+    pub moves: Vec<Move>,
+    pub score: usize,
+}
+
+/// The score for every way of mapping the `letters` of the second column, one per move, to the
+/// moves, highest first.
+pub fn mapping_scores(
+    guide: &StrategyGuide,
+    rules: &Rules,
+    letters: &[char],
+) -> Result<Vec<MappingScore>, GuideError> {
+    if letters.len() != rules.move_count() {
+        return Err(GuideError::WrongLetterCount {
+            letters: letters.len(),
+            moves: rules.move_count(),
+        });
+    }
+
+    let mut scores = permutations(rules.move_count())
+        .into_iter()
+        .map(|moves| {
+            let mut mapped_letters = vec![' '; letters.len()];
+            for (letter, mv) in letters.iter().zip(&moves) {
+                mapped_letters[*mv] = *letter;
+            }
+            let interpretation = ColumnIsMove {
+                letters: mapped_letters,
+            };
+            let score = guide.total_score(rules, &interpretation)?;
+            Ok(MappingScore { moves, score })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    scores.sort_by(|a, b| b.score.cmp(&a.score).then(a.moves.cmp(&b.moves)));
+    Ok(scores)
+}
+
+/// How many rounds are lost, drawn and won when following the guide.
+pub fn outcome_distribution(
+    guide: &StrategyGuide,
+    rules: &Rules,
+    interpretation: &dyn Interpretation,
+) -> Result<HashMap<Outcome, usize>, GuideError> {
+    let mut distribution =
+        HashMap::from([(Outcome::Lost, 0), (Outcome::Draw, 0), (Outcome::Won, 0)]);
+    for (other, myself) in guide.rounds(rules, interpretation)? {
+        *distribution.get_mut(&rules.outcome(other, myself)).unwrap() += 1;
+    }
+    Ok(distribution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guide::ColumnIsOutcome;
+
+    const EXAMPLE_INPUT: &str = "A Y\nB X\nC Z";
+
+    #[test]
+    fn max_score_works() {
+        let rules = Rules::rock_paper_scissors();
+        let guide = StrategyGuide::parse(EXAMPLE_INPUT, &rules).unwrap();
+
+        // Paper against Rock, Scissors against Paper, Rock against Scissors
+        assert_eq!(max_score(&guide, &rules), 8 + 9 + 7);
+    }
+
+    #[test]
+    fn permutations_work() {
+        let mut all = permutations(3);
+        all.sort();
+        assert_eq!(
+            all,
+            vec![
+                vec![0, 1, 2],
+                vec![0, 2, 1],
+                vec![1, 0, 2],
+                vec![1, 2, 0],
+                vec![2, 0, 1],
+                vec![2, 1, 0]
+            ]
+        );
+        assert_eq!(permutations(5).len(), 120);
+    }
+
+    #[test]
+    fn mapping_scores_work() {
+        let rules = Rules::rock_paper_scissors();
+        let guide = StrategyGuide::parse(EXAMPLE_INPUT, &rules).unwrap();
+
+        let scores = mapping_scores(&guide, &rules, &['X', 'Y', 'Z']).unwrap();
+        assert_eq!(scores.len(), 6);
+        // X for Scissors, Y for Paper, Z for Rock wins every round
+        assert_eq!(
+            scores[0],
+            MappingScore {
+                moves: vec![2, 1, 0],
+                score: 7 + 9 + 8
+            }
+        );
+        let puzzle_mapping = scores.iter().find(|s| s.moves == [0, 1, 2]).unwrap();
+        assert_eq!(puzzle_mapping.score, 15);
+
+        assert_eq!(
+            mapping_scores(&guide, &rules, &['X', 'Y']),
+            Err(GuideError::WrongLetterCount {
+                letters: 2,
+                moves: 3
+            })
+        );
+    }
+
+    #[test]
+    fn outcome_distribution_works() {
+        let rules = Rules::rock_paper_scissors();
+        let guide = StrategyGuide::parse(EXAMPLE_INPUT, &rules).unwrap();
+
+        let distribution =
+            outcome_distribution(&guide, &rules, &ColumnIsMove::up_to_z(&rules).unwrap()).unwrap();
+        assert_eq!(distribution[&Outcome::Won], 1);
+        assert_eq!(distribution[&Outcome::Lost], 1);
+        assert_eq!(distribution[&Outcome::Draw], 1);
+
+        let distribution =
+            outcome_distribution(&guide, &rules, &ColumnIsOutcome::default()).unwrap();
+        assert_eq!(distribution[&Outcome::Won], 1);
+        assert_eq!(distribution[&Outcome::Lost], 1);
+        assert_eq!(distribution[&Outcome::Draw], 1);
+    }
+}
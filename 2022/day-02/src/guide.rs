@@ -0,0 +1,231 @@
+use crate::rules::{Move, Outcome, Rules};
+
+/// Lines are numbered from 1.
+#[derive(Debug, PartialEq)]
+pub enum GuideError {
+    MalformedLine {
+        line: usize,
+    },
+    InvalidOpponentMove {
+        line: usize,
+        column: char,
+    },
+    InvalidColumn {
+        line: usize,
+        column: char,
+    },
+    /// The second column needs one letter per move of the rules.
+    WrongLetterCount {
+        letters: usize,
+        moves: usize,
+    },
+    /// There are only 26 letters up to `Z`.
+    TooManyMoves {
+        moves: usize,
+    },
+}
+
+/// A round of the strategy guide: the opponent's move and the still uninterpreted second column.
+struct GuideRound {
+    line: usize,
+    other: Move,
+    column: char,
+}
+
+pub struct StrategyGuide {
+    rounds: Vec<GuideRound>,
+}
+
+impl StrategyGuide {
+    /// The opponent's moves are the letters starting at `A`, in the order of the moves of the
+    /// rules.
+    pub fn parse(input: &str, rules: &Rules) -> Result<StrategyGuide, GuideError> {
+        let rounds = input
+            .trim()
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let line_number = index + 1;
+                let columns: Vec<char> = line
+                    .split_whitespace()
+                    .flat_map(|column| column.chars())
+                    .collect();
+                let [opponent, column] = columns[..] else {
+                    return Err(GuideError::MalformedLine { line: line_number });
+                };
+
+                let other = (opponent as usize)
+                    .checked_sub('A' as usize)
+                    .filter(|other| *other < rules.move_count())
+                    .ok_or(GuideError::InvalidOpponentMove {
+                        line: line_number,
+                        column: opponent,
+                    })?;
+
+                Ok(GuideRound {
+                    line: line_number,
+                    other,
+                    column,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(StrategyGuide { rounds })
+    }
+
+    /// The opponent's move and my move of every round.
+    pub fn rounds(
+        &self,
+        rules: &Rules,
+        interpretation: &dyn Interpretation,
+    ) -> Result<Vec<(Move, Move)>, GuideError> {
+        self.rounds
+            .iter()
+            .map(|round| {
+                let myself = interpretation
+                    .my_move(rules, round.other, round.column)
+                    .ok_or(GuideError::InvalidColumn {
+                        line: round.line,
+                        column: round.column,
+                    })?;
+                Ok((round.other, myself))
+            })
+            .collect()
+    }
+
+    /// The opponent's move of every round.
+    pub fn opponent_moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.rounds.iter().map(|round| round.other)
+    }
+
+    pub fn total_score(
+        &self,
+        rules: &Rules,
+        interpretation: &dyn Interpretation,
+    ) -> Result<usize, GuideError> {
+        Ok(self
+            .rounds(rules, interpretation)?
+            .iter()
+            .map(|(other, myself)| rules.score(*other, *myself))
+            .sum())
+    }
+}
+
+/// What the second column of the strategy guide means.
+pub trait Interpretation {
+    /// My move against `other` for the `column`, or `None` if the column is invalid.
+    fn my_move(&self, rules: &Rules, other: Move, column: char) -> Option<Move>;
+}
+
+/// The column is the move to play, each letter standing for the move at the same position.
+pub struct ColumnIsMove {
+    pub letters: Vec<char>,
+}
+
+impl ColumnIsMove {
+    /// X for Rock, Y for Paper, and Z for Scissors as in part one, or the letters up to `Z` for
+    /// more moves.
+    pub fn up_to_z(rules: &Rules) -> Result<ColumnIsMove, GuideError> {
+        let first = u8::try_from(rules.move_count())
+            .ok()
+            .and_then(|count| (b'Z' + 1).checked_sub(count))
+            .filter(|first| *first >= b'A')
+            .ok_or(GuideError::TooManyMoves {
+                moves: rules.move_count(),
+            })?;
+        Ok(ColumnIsMove {
+            letters: (first..=b'Z').map(char::from).collect(),
+        })
+    }
+}
+
+impl Interpretation for ColumnIsMove {
+    fn my_move(&self, _rules: &Rules, _other: Move, column: char) -> Option<Move> {
+        self.letters.iter().position(|letter| *letter == column)
+    }
+}
+
+/// The column is the outcome the round needs to end in.
+pub struct ColumnIsOutcome {
+    pub lose: char,
+    pub draw: char,
+    pub win: char,
+}
+
+/// X means you need to lose, Y means you need to end the round in a draw, and Z means you need to
+/// win, as in part two.
+impl Default for ColumnIsOutcome {
+    fn default() -> Self {
+        ColumnIsOutcome {
+            lose: 'X',
+            draw: 'Y',
+            win: 'Z',
+        }
+    }
+}
+
+impl Interpretation for ColumnIsOutcome {
+    fn my_move(&self, rules: &Rules, other: Move, column: char) -> Option<Move> {
+        let outcome = match column {
+            c if c == self.lose => Outcome::Lost,
+            c if c == self.draw => Outcome::Draw,
+            c if c == self.win => Outcome::Won,
+            _ => return None,
+        };
+        rules.move_for_outcome(other, outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let rules = Rules::rock_paper_scissors();
+
+        assert_eq!(
+            StrategyGuide::parse("A Y\nD X", &rules).err(),
+            Some(GuideError::InvalidOpponentMove {
+                line: 2,
+                column: 'D'
+            })
+        );
+        assert_eq!(
+            StrategyGuide::parse("A Y\nB", &rules).err(),
+            Some(GuideError::MalformedLine { line: 2 })
+        );
+
+        let guide = StrategyGuide::parse("A Y\nB W", &rules).unwrap();
+        let interpretation = ColumnIsMove {
+            letters: vec!['X', 'Y', 'Z'],
+        };
+        assert_eq!(
+            guide.total_score(&rules, &interpretation),
+            Err(GuideError::InvalidColumn {
+                line: 2,
+                column: 'W'
+            })
+        );
+    }
+
+    #[test]
+    fn up_to_z_works() {
+        let letters = ColumnIsMove::up_to_z(&Rules::rock_paper_scissors())
+            .unwrap()
+            .letters;
+        assert_eq!(letters, vec!['X', 'Y', 'Z']);
+
+        let moves: Vec<_> = (0..27).map(|mv| format!("Move {mv}")).collect();
+        let table: Vec<_> = moves.iter().map(|name| (name.as_str(), 1)).collect();
+        let alphabet = Rules::new(&table[..26], &[], [0, 3, 6]).unwrap();
+        let letters = ColumnIsMove::up_to_z(&alphabet).unwrap().letters;
+        assert_eq!(letters.first(), Some(&'A'));
+
+        let too_many = Rules::new(&table, &[], [0, 3, 6]).unwrap();
+        assert_eq!(
+            ColumnIsMove::up_to_z(&too_many).err(),
+            Some(GuideError::TooManyMoves { moves: 27 })
+        );
+    }
+}
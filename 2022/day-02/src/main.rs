@@ -1,158 +1,73 @@
 use std::{fs::File, io::Read};
 
-#[derive(Clone, PartialEq)]
-enum Move {
-    Rock,
-    Paper,
-    Scissors,
-}
-
-impl Move {
-    fn winning_move(&self) -> Move {
-        match self {
-            Move::Rock => Move::Paper,
-            Move::Paper => Move::Scissors,
-            Move::Scissors => Move::Rock,
-        }
-    }
-
-    fn loosing_move(&self) -> Move {
-        match self {
-            Move::Rock => Move::Scissors,
-            Move::Paper => Move::Rock,
-            Move::Scissors => Move::Paper,
-        }
-    }
-}
-
-enum Outcome {
-    Lost,
-    Draw,
-    Won,
-}
-
-struct Round {
-    other: Move,
-    myself: Move,
-}
-
-impl Round {
-    /// The score for a single round is the score for the shape you selected (1 for Rock, 2 for Paper, and 3 for Scissors)
-    /// plus the score for the outcome of the round (0 if you lost, 3 if the round was a draw, and 6 if you won).
-    fn score(&self) -> usize {
-        let mut score: usize = match self.myself {
-            Move::Rock => 1,
-            Move::Paper => 2,
-            Move::Scissors => 3,
-        };
-
-        score += match self.outcome() {
-            Outcome::Lost => 0,
-            Outcome::Draw => 3,
-            Outcome::Won => 6,
-        };
-
-        score
+use day_02::{
+    analysis,
+    guide::{ColumnIsMove, ColumnIsOutcome, Interpretation, StrategyGuide},
+    rules::{Outcome, Rules},
+};
+
+fn print_report(guide: &StrategyGuide, rules: &Rules) {
+    println!("Maximum score: {}", analysis::max_score(guide, rules));
+
+    let letters = ColumnIsMove::up_to_z(rules).unwrap().letters;
+    println!("Scores by mapping:");
+    for mapping in analysis::mapping_scores(guide, rules, &letters).unwrap() {
+        let moves: Vec<_> = letters
+            .iter()
+            .zip(&mapping.moves)
+            .map(|(letter, mv)| format!("{letter}={}", rules.name(*mv)))
+            .collect();
+        println!("  {}: {}", moves.join(" "), mapping.score);
     }
 
-    fn outcome(&self) -> Outcome {
-        match (&self.other, &self.myself) {
-            (Move::Rock, Move::Paper) => Outcome::Won,
-            (Move::Rock, Move::Rock) => Outcome::Draw,
-            (Move::Rock, Move::Scissors) => Outcome::Lost,
-            (Move::Paper, Move::Rock) => Outcome::Lost,
-            (Move::Paper, Move::Paper) => Outcome::Draw,
-            (Move::Paper, Move::Scissors) => Outcome::Won,
-            (Move::Scissors, Move::Rock) => Outcome::Won,
-            (Move::Scissors, Move::Scissors) => Outcome::Draw,
-            (Move::Scissors, Move::Paper) => Outcome::Lost,
-        }
-    }
-}
-
-mod part_one {
-    use crate::*;
-
-    fn parse_round(input: &str) -> Round {
-        let elements: Vec<&str> = input.split_whitespace().collect();
-
-        // First col: A for Rock, B for Paper, and C for Scissors
-        let other = match elements[0] {
-            "A" => Move::Rock,
-            "B" => Move::Paper,
-            "C" => Move::Scissors,
-            _ => panic!("Invalid move: {}", input),
-        };
-
-        // Second col: X for Rock, Y for Paper, and Z for Scissors
-        let myself = match elements[1] {
-            "X" => Move::Rock,
-            "Y" => Move::Paper,
-            "Z" => Move::Scissors,
-            _ => panic!("Invalid move: {}", input),
-        };
-
-        Round { other, myself }
-    }
-
-    fn parse_rounds(input: &str) -> Vec<Round> {
-        input
-            .trim()
-            .split("\n")
-            .map(|line| parse_round(line))
-            .collect()
-    }
-
-    pub fn rock_paper_scissors_score(input: &str) -> usize {
-        parse_rounds(input).iter().map(|round| round.score()).sum()
-    }
-}
-
-mod part_two {
-    use crate::*;
-
-    fn parse_round(input: &str) -> Round {
-        let elements: Vec<&str> = input.split_whitespace().collect();
-
-        // First col: A for Rock, B for Paper, and C for Scissors
-        let other = match elements[0] {
-            "A" => Move::Rock,
-            "B" => Move::Paper,
-            "C" => Move::Scissors,
-            _ => panic!("Invalid move: {}", input),
-        };
-
-        // Second col: X means you need to lose, Y means you need to end the round in a draw, and Z means you need to win
-        let myself = match elements[1] {
-            "X" => other.loosing_move(),
-            "Y" => other.clone(),
-            "Z" => other.winning_move(),
-            _ => panic!("Invalid move: {}", input),
-        };
-
-        Round { other, myself }
-    }
-
-    fn parse_rounds(input: &str) -> Vec<Round> {
-        input
-            .trim()
-            .split("\n")
-            .map(|line| parse_round(line))
-            .collect()
-    }
-
-    pub fn rock_paper_scissors_score(input: &str) -> usize {
-        parse_rounds(input).iter().map(|round| round.score()).sum()
+    let interpretations: [(&str, &dyn Interpretation); 2] = [
+        ("Part One", &ColumnIsMove::up_to_z(rules).unwrap()),
+        ("Part Two", &ColumnIsOutcome::default()),
+    ];
+    for (name, interpretation) in interpretations {
+        let distribution = analysis::outcome_distribution(guide, rules, interpretation).unwrap();
+        println!(
+            "{name} outcomes: {} won, {} drawn, {} lost",
+            distribution[&Outcome::Won],
+            distribution[&Outcome::Draw],
+            distribution[&Outcome::Lost]
+        );
     }
 }
 
+/// Usage: `day-02 [--lizard-spock] [--report]`. With `--lizard-spock` Rock Paper Scissors Lizard
+/// Spock is played, the opponent's moves being `A` to `E` and mine `V` to `Z`. `--report` analyses
+/// the guide: the maximum achievable score, the score for every mapping of the second column to
+/// moves and how many rounds are won, drawn and lost.
 fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let rules = if args.iter().any(|arg| arg == "--lizard-spock") {
+        Rules::rock_paper_scissors_lizard_spock()
+    } else {
+        Rules::rock_paper_scissors()
+    };
+
     let mut f = File::open("input.txt").unwrap();
     let mut input = String::new();
     f.read_to_string(&mut input).unwrap();
 
-    println!("Part One: {}", part_one::rock_paper_scissors_score(&input));
-    println!("Part Two: {}", part_two::rock_paper_scissors_score(&input));
+    let guide = StrategyGuide::parse(&input, &rules).unwrap();
+    println!(
+        "Part One: {}",
+        guide
+            .total_score(&rules, &ColumnIsMove::up_to_z(&rules).unwrap())
+            .unwrap()
+    );
+    println!(
+        "Part Two: {}",
+        guide
+            .total_score(&rules, &ColumnIsOutcome::default())
+            .unwrap()
+    );
+
+    if args.iter().any(|arg| arg == "--report") {
+        print_report(&guide, &rules);
+    }
 }
 
 #[cfg(test)]
@@ -164,10 +79,30 @@ mod tests {
         let input = "A Y
         B X
         C Z";
-        let part_one = part_one::rock_paper_scissors_score(input);
-        assert_eq!(part_one, 15);
+        let rules = Rules::rock_paper_scissors();
+        let guide = StrategyGuide::parse(input, &rules).unwrap();
+
+        let part_one = guide.total_score(&rules, &ColumnIsMove::up_to_z(&rules).unwrap());
+        assert_eq!(part_one, Ok(15));
 
-        let part_two = part_two::rock_paper_scissors_score(input);
-        assert_eq!(part_two, 12);
+        let part_two = guide.total_score(&rules, &ColumnIsOutcome::default());
+        assert_eq!(part_two, Ok(12));
+    }
+
+    #[test]
+    fn lizard_spock_works() {
+        let input = "A V\nE W\nD Z\nC Y";
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let guide = StrategyGuide::parse(input, &rules).unwrap();
+
+        // Rock vs Rock, Spock vs Paper, Lizard vs Spock, Scissors vs Lizard
+        let part_one = guide.total_score(&rules, &ColumnIsMove::up_to_z(&rules).unwrap());
+        assert_eq!(part_one, Ok((1 + 3) + (2 + 6) + 5 + 4));
+
+        // Lizard to lose against Rock, Spock to draw, Scissors to win against Lizard and Spock to
+        // win against Scissors, choosing the higher scoring move where two would do
+        let guide = StrategyGuide::parse("A X\nE Y\nD Z\nC Z", &rules).unwrap();
+        let part_two = guide.total_score(&rules, &ColumnIsOutcome::default());
+        assert_eq!(part_two, Ok(4 + (5 + 3) + (3 + 6) + (5 + 6)));
     }
 }
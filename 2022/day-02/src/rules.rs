@@ -0,0 +1,217 @@
+/// A move, as index into the moves of the [`Rules`].
+pub type Move = usize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lost,
+    Draw,
+    Won,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RulesError {
+    /// The `beats` table names a move that isn't one of the moves.
+    UnknownMove { name: String },
+    /// Both moves are said to beat each other, or a move to beat itself.
+    Contradiction { winner: String, loser: String },
+}
+
+/// The game as a table: the moves with the score for choosing them, which move beats which, and
+/// the score for every outcome. Moves neither beating nor being beaten by each other are a draw.
+pub struct Rules {
+    names: Vec<String>,
+    shape_scores: Vec<usize>,
+    /// `beats[a][b]` is set if move `a` beats move `b`
+    beats: Vec<Vec<bool>>,
+    lost_score: usize,
+    draw_score: usize,
+    won_score: usize,
+}
+
+impl Rules {
+    /// `moves` are the names of the moves with their shape score, `beats` the pairs of the
+    /// winning and the losing move by name.
+    pub fn new(
+        moves: &[(&str, usize)],
+        beats: &[(&str, &str)],
+        [lost_score, draw_score, won_score]: [usize; 3],
+    ) -> Result<Rules, RulesError> {
+        let index = |name: &str| {
+            moves
+                .iter()
+                .position(|(n, _)| *n == name)
+                .ok_or_else(|| RulesError::UnknownMove {
+                    name: name.to_string(),
+                })
+        };
+
+        let mut beat_table = vec![vec![false; moves.len()]; moves.len()];
+        for (winner, loser) in beats {
+            let (winner_index, loser_index) = (index(winner)?, index(loser)?);
+            if winner_index == loser_index || beat_table[loser_index][winner_index] {
+                return Err(RulesError::Contradiction {
+                    winner: winner.to_string(),
+                    loser: loser.to_string(),
+                });
+            }
+            beat_table[winner_index][loser_index] = true;
+        }
+
+        Ok(Rules {
+            names: moves.iter().map(|(name, _)| name.to_string()).collect(),
+            shape_scores: moves.iter().map(|(_, score)| *score).collect(),
+            beats: beat_table,
+            lost_score,
+            draw_score,
+            won_score,
+        })
+    }
+
+    /// Rock, Paper and Scissors scoring 1, 2 and 3, with 0 for a loss, 3 for a draw and 6 for a
+    /// win.
+    pub fn rock_paper_scissors() -> Rules {
+        Rules::new(
+            &[("Rock", 1), ("Paper", 2), ("Scissors", 3)],
+            &[
+                ("Rock", "Scissors"),
+                ("Paper", "Rock"),
+                ("Scissors", "Paper"),
+            ],
+            [0, 3, 6],
+        )
+        .unwrap()
+    }
+
+    /// Rock Paper Scissors Lizard Spock, the two added moves scoring 4 and 5.
+    pub fn rock_paper_scissors_lizard_spock() -> Rules {
+        Rules::new(
+            &[
+                ("Rock", 1),
+                ("Paper", 2),
+                ("Scissors", 3),
+                ("Lizard", 4),
+                ("Spock", 5),
+            ],
+            &[
+                ("Scissors", "Paper"),
+                ("Paper", "Rock"),
+                ("Rock", "Lizard"),
+                ("Lizard", "Spock"),
+                ("Spock", "Scissors"),
+                ("Scissors", "Lizard"),
+                ("Lizard", "Paper"),
+                ("Paper", "Spock"),
+                ("Spock", "Rock"),
+                ("Rock", "Scissors"),
+            ],
+            [0, 3, 6],
+        )
+        .unwrap()
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.shape_scores.len()
+    }
+
+    pub fn name(&self, mv: Move) -> &str {
+        &self.names[mv]
+    }
+
+    pub fn outcome(&self, other: Move, myself: Move) -> Outcome {
+        if self.beats[myself][other] {
+            Outcome::Won
+        } else if self.beats[other][myself] {
+            Outcome::Lost
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The score for the shape selected plus the score for the outcome of the round.
+    pub fn score(&self, other: Move, myself: Move) -> usize {
+        let outcome_score = match self.outcome(other, myself) {
+            Outcome::Lost => self.lost_score,
+            Outcome::Draw => self.draw_score,
+            Outcome::Won => self.won_score,
+        };
+        self.shape_scores[myself] + outcome_score
+    }
+
+    /// The move leading to `outcome` against `other`. If several do, the one scoring most is
+    /// chosen.
+    pub fn move_for_outcome(&self, other: Move, outcome: Outcome) -> Option<Move> {
+        (0..self.move_count())
+            .filter(|myself| self.outcome(other, *myself) == outcome)
+            .max_by_key(|myself| self.shape_scores[*myself])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rock_paper_scissors_works() {
+        let rules = Rules::rock_paper_scissors();
+        let (rock, paper, scissors) = (0, 1, 2);
+
+        assert_eq!(rules.outcome(rock, paper), Outcome::Won);
+        assert_eq!(rules.outcome(scissors, paper), Outcome::Lost);
+        assert_eq!(rules.outcome(paper, paper), Outcome::Draw);
+        assert_eq!(rules.score(rock, paper), 8);
+        assert_eq!(rules.score(paper, rock), 1);
+        assert_eq!(rules.score(scissors, scissors), 6);
+
+        assert_eq!(rules.move_for_outcome(rock, Outcome::Won), Some(paper));
+        assert_eq!(rules.move_for_outcome(rock, Outcome::Lost), Some(scissors));
+        assert_eq!(rules.move_for_outcome(rock, Outcome::Draw), Some(rock));
+    }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock_works() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let (rock, paper, scissors, lizard, spock) = (0, 1, 2, 3, 4);
+
+        // Every move beats exactly two others
+        for mv in 0..rules.move_count() {
+            let wins = (0..rules.move_count())
+                .filter(|other| rules.outcome(*other, mv) == Outcome::Won)
+                .count();
+            assert_eq!(wins, 2, "{}", rules.name(mv));
+        }
+
+        assert_eq!(rules.outcome(spock, lizard), Outcome::Won);
+        assert_eq!(rules.outcome(lizard, spock), Outcome::Lost);
+        // Both Paper and Spock beat Rock, Spock scores more
+        assert_eq!(rules.move_for_outcome(rock, Outcome::Won), Some(spock));
+        assert_eq!(rules.move_for_outcome(spock, Outcome::Lost), Some(scissors));
+        assert_eq!(rules.move_for_outcome(paper, Outcome::Draw), Some(paper));
+    }
+
+    #[test]
+    fn invalid_tables_fail() {
+        let moves = [("Rock", 1), ("Paper", 2)];
+        assert_eq!(
+            Rules::new(&moves, &[("Paper", "Stone")], [0, 3, 6]).err(),
+            Some(RulesError::UnknownMove {
+                name: "Stone".to_string()
+            })
+        );
+        assert_eq!(
+            Rules::new(&moves, &[("Paper", "Rock"), ("Rock", "Paper")], [0, 3, 6]).err(),
+            Some(RulesError::Contradiction {
+                winner: "Rock".to_string(),
+                loser: "Paper".to_string()
+            })
+        );
+        assert_eq!(
+            Rules::new(&moves, &[("Rock", "Rock")], [0, 3, 6]).err(),
+            Some(RulesError::Contradiction {
+                winner: "Rock".to_string(),
+                loser: "Rock".to_string()
+            })
+        );
+        // Repeating a pair is no contradiction
+        assert!(Rules::new(&moves, &[("Paper", "Rock"), ("Paper", "Rock")], [0, 3, 6]).is_ok());
+    }
+}
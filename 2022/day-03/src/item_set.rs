@@ -0,0 +1,106 @@
+use std::ops::{BitAnd, BitOr, Sub};
+
+use bitmaps::Bitmap;
+
+/// An item that isn't a letter, so it has no priority.
+#[derive(Debug, PartialEq)]
+pub struct InvalidItem(pub char);
+
+/// The priority of an item: `a` to `z` are 1 to 26, `A` to `Z` are 27 to 52.
+pub fn priority(item: char) -> Result<usize, InvalidItem> {
+    match item {
+        'a'..='z' => Ok(item as usize - 'a' as usize + 1),
+        'A'..='Z' => Ok(item as usize - 'A' as usize + 27),
+        _ => Err(InvalidItem(item)),
+    }
+}
+
+/// A set of items, stored as a bitmap indexed by priority.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ItemSet {
+    bitmap: Bitmap<64>,
+}
+
+impl ItemSet {
+    pub fn new() -> ItemSet {
+        ItemSet {
+            bitmap: Bitmap::new(),
+        }
+    }
+
+    pub fn from_items(items: &str) -> Result<ItemSet, InvalidItem> {
+        let mut set = ItemSet::new();
+        for item in items.chars() {
+            set.bitmap.set(priority(item)?, true);
+        }
+        Ok(set)
+    }
+
+    /// The priorities of the items, lowest first.
+    pub fn priorities(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bitmap.into_iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+}
+
+/// The union of both sets.
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        ItemSet {
+            bitmap: self.bitmap | other.bitmap,
+        }
+    }
+}
+
+/// The intersection of both sets.
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        ItemSet {
+            bitmap: self.bitmap & other.bitmap,
+        }
+    }
+}
+
+/// The items of the first set which aren't in the second one.
+impl Sub for ItemSet {
+    type Output = ItemSet;
+
+    fn sub(self, other: ItemSet) -> ItemSet {
+        ItemSet {
+            bitmap: self.bitmap & !other.bitmap,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_works() {
+        assert_eq!(priority('p'), Ok(16));
+        assert_eq!(priority('L'), Ok(38));
+        assert_eq!(priority('1'), Err(InvalidItem('1')));
+    }
+
+    #[test]
+    fn set_operations_work() {
+        let a = ItemSet::from_items("abcA").unwrap();
+        let b = ItemSet::from_items("bcdB").unwrap();
+
+        let priorities = |set: ItemSet| set.priorities().collect::<Vec<_>>();
+        assert_eq!(priorities(a | b), vec![1, 2, 3, 4, 27, 28]);
+        assert_eq!(priorities(a & b), vec![2, 3]);
+        assert_eq!(priorities(a - b), vec![1, 27]);
+        assert_eq!(a.len(), 4);
+        assert_eq!((a - a).len(), 0);
+        assert_eq!(ItemSet::from_items("ab-"), Err(InvalidItem('-')));
+    }
+}
@@ -1,102 +1,215 @@
-use bitmaps::Bitmap;
-use itertools::Itertools;
 use std::{fs::File, io::Read};
 
-fn byte_to_priority(byte: &u8) -> usize {
-    let priority = match byte {
-        97..=122 => byte - 97 + 1,
-        65..=90 => byte - 65 + 27,
-        _ => panic!(),
-    };
-    priority as usize
-}
+mod item_set;
 
-fn calc_rucksack_priority(input: &str) -> usize {
-    let bytes = input.as_bytes();
-
-    let mut bitmap: Bitmap<64> = Bitmap::new();
-    for byte in &bytes[0..bytes.len() / 2] {
-        let priority = byte_to_priority(byte);
-        bitmap.set(priority, true);
-    }
+use item_set::{InvalidItem, ItemSet};
 
-    for byte in &bytes[bytes.len() / 2..] {
-        let priority = byte_to_priority(byte);
-        if bitmap.get(priority) {
-            return priority;
-        }
-    }
-
-    panic!()
+/// The item sets compared for a common item: the compartments of a rucksack, or the rucksacks of
+/// a group.
+#[derive(Debug, PartialEq)]
+enum Compared {
+    Rucksack(usize),
+    Group(usize),
 }
 
-fn rucksack_bitmap(input: &str) -> Bitmap<64> {
-    let bytes = input.trim().as_bytes();
+/// Rucksacks and groups are numbered from 1, in the order they appear in the input.
+#[derive(Debug, PartialEq)]
+enum RucksackError {
+    InvalidItem(char),
+    /// Rucksacks can't be split into zero compartments, nor elves into groups of zero.
+    ZeroGroupSize,
+    /// The items of a rucksack can't be split evenly into the compartments.
+    UnevenCompartments {
+        rucksack: usize,
+    },
+    /// The last group has fewer rucksacks than the group size.
+    IncompleteGroup {
+        group: usize,
+    },
+    NoCommonItem {
+        compared: Compared,
+    },
+    MultipleCommonItems {
+        compared: Compared,
+        priorities: Vec<usize>,
+    },
+}
 
-    let mut bitmap: Bitmap<64> = Bitmap::new();
-    for byte in bytes {
-        let priority = byte_to_priority(byte);
-        bitmap.set(priority, true);
+impl From<InvalidItem> for RucksackError {
+    fn from(InvalidItem(item): InvalidItem) -> Self {
+        RucksackError::InvalidItem(item)
     }
+}
 
-    bitmap
+fn rucksacks(input: &str) -> impl Iterator<Item = &str> {
+    input.lines().map(str::trim).filter(|line| !line.is_empty())
 }
 
-fn find_badge_item(bitmaps: [Bitmap<64>; 3]) -> usize {
-    let [map1, map2, map3] = bitmaps;
-    let badge_map = map1 & map2 & map3;
-    badge_map.first_index().unwrap()
+/// The priority of the one item all sets of a group have in common.
+fn common_item_priority(sets: &[ItemSet], compared: Compared) -> Result<usize, RucksackError> {
+    let common = sets
+        .iter()
+        .skip(1)
+        .fold(sets[0], |common, set| common & *set);
+
+    match common.len() {
+        0 => Err(RucksackError::NoCommonItem { compared }),
+        1 => Ok(common.priorities().next().unwrap()),
+        _ => Err(RucksackError::MultipleCommonItems {
+            compared,
+            priorities: common.priorities().collect(),
+        }),
+    }
 }
 
-fn rucksack_reorganization(input: &str) -> usize {
-    input
-        .split("\n")
-        .map(|line| calc_rucksack_priority(line.trim()))
+/// Sums the priorities of the items found in all `compartments` of each rucksack, every rucksack
+/// being split into compartments of equal size.
+fn rucksack_reorganization(input: &str, compartments: usize) -> Result<usize, RucksackError> {
+    if compartments == 0 {
+        return Err(RucksackError::ZeroGroupSize);
+    }
+
+    rucksacks(input)
+        .enumerate()
+        .map(|(index, rucksack)| {
+            let rucksack_number = index + 1;
+            let items: Vec<char> = rucksack.chars().collect();
+            if !items.len().is_multiple_of(compartments) {
+                return Err(RucksackError::UnevenCompartments {
+                    rucksack: rucksack_number,
+                });
+            }
+
+            let sets = items
+                .chunks(items.len() / compartments)
+                .map(|items| ItemSet::from_items(&items.iter().collect::<String>()))
+                .collect::<Result<Vec<_>, _>>()?;
+            common_item_priority(&sets, Compared::Rucksack(rucksack_number))
+        })
         .sum()
 }
 
-fn rucksack_badges(input: &str) -> usize {
-    let mut sum = 0;
-    for chunk in &input.split("\n").into_iter().chunks(3) {
-        let bitmaps: Vec<_> = chunk.map(|line| rucksack_bitmap(line)).collect();
-        sum += find_badge_item(bitmaps.try_into().unwrap());
+/// Sums the priorities of the badges, the items carried by all rucksacks of a group of
+/// `group_size` elves.
+fn rucksack_badges(input: &str, group_size: usize) -> Result<usize, RucksackError> {
+    if group_size == 0 {
+        return Err(RucksackError::ZeroGroupSize);
     }
-
-    sum
+    let sets = rucksacks(input)
+        .map(ItemSet::from_items)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    sets.chunks(group_size)
+        .enumerate()
+        .map(|(index, group_sets)| {
+            let group = index + 1;
+            if group_sets.len() < group_size {
+                return Err(RucksackError::IncompleteGroup { group });
+            }
+            common_item_priority(group_sets, Compared::Group(group))
+        })
+        .sum()
 }
 
+/// Usage: `day-03 [--compartments <n>] [--group-size <n>]`, defaulting to the puzzle's two
+/// compartments per rucksack and groups of three elves.
 fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let arg_value = |name: &str, default: usize| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map_or(default, |index| match args.get(index + 1) {
+                Some(value) => value.parse().unwrap(),
+                None => panic!("{name} requires a value"),
+            })
+    };
+
     let mut f = File::open("input.txt").unwrap();
     let mut input = String::new();
     f.read_to_string(&mut input).unwrap();
 
-    println!("Part One: {}", rucksack_reorganization(&input));
-    println!("Part Two: {}", rucksack_badges(&input));
+    let compartments = arg_value("--compartments", 2);
+    let group_size = arg_value("--group-size", 3);
+    println!(
+        "Part One: {}",
+        rucksack_reorganization(&input, compartments).unwrap()
+    );
+    println!("Part Two: {}", rucksack_badges(&input, group_size).unwrap());
 }
 
 #[cfg(test)]
 mod tests {
     use crate::*;
 
-    #[test]
-    fn byte_to_priority_works() {
-        assert_eq!(byte_to_priority(&('p' as u8)), 16);
-        assert_eq!(byte_to_priority(&('L' as u8)), 38);
-    }
-
-    #[test]
-    fn example_works() {
-        let input = "vJrwpWtwJgWrhcsFMMfFFhFp
+    const EXAMPLE_INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
         jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
         PmmdzqPrVvPwwTWBwg
         wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
         ttgJtRGJQctTZtZT
         CrZsJsPPZsGzwwsLwLmpwMDw";
 
-        let part_one = rucksack_reorganization(input);
-        assert_eq!(part_one, 157);
+    #[test]
+    fn example_works() {
+        let part_one = rucksack_reorganization(EXAMPLE_INPUT, 2);
+        assert_eq!(part_one, Ok(157));
+
+        let part_two = rucksack_badges(EXAMPLE_INPUT, 3);
+        assert_eq!(part_two, Ok(70));
+    }
 
-        let part_two = rucksack_badges(input);
-        assert_eq!(part_two, 70);
+    #[test]
+    fn group_sizes_work() {
+        assert_eq!(rucksack_badges("abc\nbxd\ncye\ncxz", 2), Ok(2 + 3));
+        assert_eq!(rucksack_badges("xa\nxb\nxc\nxd", 4), Ok(24));
+        assert_eq!(rucksack_reorganization("abcaxa\nZbZcZd", 3), Ok(1 + 52));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(
+            rucksack_badges("ab\ncd", 2),
+            Err(RucksackError::NoCommonItem {
+                compared: Compared::Group(1)
+            })
+        );
+        assert_eq!(
+            rucksack_badges("ab\nab\nb", 2),
+            Err(RucksackError::MultipleCommonItems {
+                compared: Compared::Group(1),
+                priorities: vec![1, 2]
+            })
+        );
+        assert_eq!(rucksack_badges("ab\nab\nb", 3), Ok(2));
+        assert_eq!(
+            rucksack_badges("ab\nab", 3),
+            Err(RucksackError::IncompleteGroup { group: 1 })
+        );
+        assert_eq!(
+            rucksack_reorganization("aa\naba", 2),
+            Err(RucksackError::UnevenCompartments { rucksack: 2 })
+        );
+        assert_eq!(
+            rucksack_reorganization("abcb\nabcd", 2),
+            Err(RucksackError::NoCommonItem {
+                compared: Compared::Rucksack(2)
+            })
+        );
+        assert_eq!(
+            rucksack_reorganization("a1", 2),
+            Err(RucksackError::InvalidItem('1'))
+        );
+        assert_eq!(
+            rucksack_reorganization("aäb", 2),
+            Err(RucksackError::UnevenCompartments { rucksack: 1 })
+        );
+        assert_eq!(
+            rucksack_reorganization("aäba", 2),
+            Err(RucksackError::InvalidItem('ä'))
+        );
+        assert_eq!(
+            rucksack_reorganization("abab", 0),
+            Err(RucksackError::ZeroGroupSize)
+        );
+        assert_eq!(rucksack_badges("ab", 0), Err(RucksackError::ZeroGroupSize));
     }
 }
@@ -0,0 +1,334 @@
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive ranges, so sets of
+/// huge ranges take as little space as the number of ranges they consist of. Inclusive bounds
+/// allow the whole `i64` domain, including `i64::MAX`, to be represented.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<i64>>,
+}
+
+/// Converts any range, inclusive or half-open, to an inclusive one, or `None` if it is empty.
+/// Unbounded ends reach to the smallest and largest `i64`.
+fn inclusive(range: impl RangeBounds<i64>) -> Option<RangeInclusive<i64>> {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.checked_add(1)?,
+        Bound::Unbounded => i64::MIN,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => *end,
+        Bound::Excluded(end) => end.checked_sub(1)?,
+        Bound::Unbounded => i64::MAX,
+    };
+    (start <= end).then_some(start..=end)
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    /// Builds the set from non-empty ranges in any order, merging overlapping and adjacent ones.
+    fn from_ranges(mut ranges: Vec<RangeInclusive<i64>>) -> IntervalSet {
+        ranges.sort_by_key(|range| *range.start());
+
+        let mut merged: Vec<RangeInclusive<i64>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                    *last = *last.start()..=*last.end().max(range.end());
+                }
+                _ => merged.push(range),
+            }
+        }
+        IntervalSet { ranges: merged }
+    }
+
+    /// The inclusive ranges the set consists of, ordered by start.
+    pub fn ranges(&self) -> &[RangeInclusive<i64>] {
+        &self.ranges
+    }
+
+    /// Merges the range into the set, replacing the run of ranges it overlaps or touches. Finding
+    /// the run is a binary search, so building a set one range at a time doesn't re-sort it.
+    pub fn insert(&mut self, range: impl RangeBounds<i64>) {
+        let Some(range) = inclusive(range) else {
+            return;
+        };
+        let (mut start, mut end) = range.into_inner();
+
+        let first = self
+            .ranges
+            .partition_point(|other| other.end().saturating_add(1) < start);
+        let last = self
+            .ranges
+            .partition_point(|other| *other.start() <= end.saturating_add(1));
+        if first < last {
+            start = start.min(*self.ranges[first].start());
+            end = end.max(*self.ranges[last - 1].end());
+        }
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        IntervalSet::from_ranges(self.ranges.iter().chain(&other.ranges).cloned().collect())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let (start, end) = (*a.start().max(b.start()), *a.end().min(b.end()));
+            if start <= end {
+                ranges.push(start..=end);
+            }
+            // The range ending first can't overlap any further range of the other set
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The values of this set which aren't in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for range in &self.ranges {
+            // The first value of the range not yet known to be in `other`, `None` once the rest
+            // of the range is covered by it
+            let mut start = Some(*range.start());
+            while j < other.ranges.len() && other.ranges[j].end() < range.start() {
+                j += 1;
+            }
+
+            let mut k = j;
+            while let Some(current) = start {
+                let Some(cut) = other.ranges.get(k).filter(|cut| cut.start() <= range.end()) else {
+                    break;
+                };
+                if *cut.start() > current {
+                    ranges.push(current..=cut.start() - 1);
+                }
+                start = match cut.end() < range.end() {
+                    true => Some(current.max(cut.end() + 1)),
+                    false => None,
+                };
+                k += 1;
+            }
+            if let Some(start) = start {
+                ranges.push(start..=*range.end());
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self.ranges.partition_point(|range| *range.end() < value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&value))
+    }
+
+    /// Whether every value of `other` is in this set as well.
+    pub fn is_superset(&self, other: &IntervalSet) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of values in the set, which for the whole `i64` domain exceeds a `u64`.
+    pub fn total_len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|range| range.end().abs_diff(*range.start()) as u128 + 1)
+            .sum()
+    }
+}
+
+impl<R: RangeBounds<i64>> FromIterator<R> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = R>>(ranges: T) -> Self {
+        IntervalSet::from_ranges(ranges.into_iter().filter_map(inclusive).collect())
+    }
+}
+
+impl<R: RangeBounds<i64>> From<R> for IntervalSet {
+    fn from(range: R) -> Self {
+        IntervalSet::from_iter([range])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+
+    use proptest::prelude::*;
+
+    /// The model covers the `MODEL_SIZE` values starting at a base, small enough to keep a flag
+    /// for each.
+    const MODEL_SIZE: i64 = 64;
+
+    /// Bases placing the model at zero and at both extremes of the `i64` domain.
+    const MODEL_BASES: [i64; 3] = [0, i64::MIN, i64::MAX - (MODEL_SIZE - 1)];
+
+    fn model(set: &IntervalSet, base: i64) -> Vec<bool> {
+        (0..MODEL_SIZE)
+            .map(|offset| set.contains(base + offset))
+            .collect()
+    }
+
+    fn model_of(ranges: &[RangeInclusive<i64>], base: i64) -> Vec<bool> {
+        (0..MODEL_SIZE)
+            .map(|offset| ranges.iter().any(|range| range.contains(&(base + offset))))
+            .collect()
+    }
+
+    /// Inclusive ranges within the model, possibly empty, with the model's base.
+    fn ranges_strategy() -> impl Strategy<Value = (Vec<RangeInclusive<i64>>, i64)> {
+        prop::sample::select(MODEL_BASES.to_vec()).prop_flat_map(|base| {
+            let range = (0..MODEL_SIZE, 0..16i64).prop_map(move |(offset, len)| {
+                let start = base + offset;
+                start..=start.saturating_add(len - 1).min(base + (MODEL_SIZE - 1))
+            });
+            (prop::collection::vec(range, 0..8), Just(base))
+        })
+    }
+
+    /// The ranges must be sorted, disjoint, non-adjacent and non-empty.
+    fn assert_normalized(set: &IntervalSet) {
+        for range in set.ranges() {
+            assert!(range.start() <= range.end());
+        }
+        for pair in set.ranges().windows(2) {
+            assert!(*pair[0].end() < pair[1].start() - 1);
+        }
+    }
+
+    #[test]
+    fn inclusive_and_half_open_ranges_work() {
+        let mut set = IntervalSet::from(2..=4);
+        assert_eq!(set, IntervalSet::from(2..5));
+
+        set.insert(5..7);
+        set.insert(10..=10);
+        assert_eq!(set.ranges(), &[2..=6, 10..=10]);
+        assert_eq!(set.total_len(), 6);
+        assert!(set.contains(6));
+        assert!(!set.contains(7));
+
+        // Bridges the gap between two ranges and swallows the one in between
+        let mut set = IntervalSet::from_iter([0..=1, 4..=4, 6..=7, 20..=21]);
+        set.insert(2..=5);
+        assert_eq!(set.ranges(), &[0..=7, 20..=21]);
+        set.insert(i64::MIN..=-5);
+        set.insert(22..);
+        assert_eq!(set.ranges(), &[i64::MIN..=-5, 0..=7, 20..=i64::MAX]);
+
+        let set: IntervalSet = [8..=9, 1..=3].into_iter().collect();
+        assert_eq!(set.ranges(), &[1..=3, 8..=9]);
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = IntervalSet::from_iter([3..3, i64::MIN..i64::MIN, 5..2]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn extremes_work() {
+        let set = IntervalSet::from(0..=i64::MAX);
+        assert!(set.contains(i64::MAX));
+        assert_eq!(set.total_len(), i64::MAX as u128 + 1);
+
+        let all = IntervalSet::from(..);
+        assert!(all.contains(i64::MIN) && all.contains(i64::MAX));
+        assert_eq!(all.total_len(), u64::MAX as u128 + 1);
+        assert_eq!(all, IntervalSet::from(i64::MIN..=-1).union(&set));
+        assert_eq!(all.difference(&set), IntervalSet::from(..0));
+        assert!(all.difference(&all).is_empty());
+
+        let excluded = IntervalSet::from((Bound::Excluded(i64::MAX), Bound::Unbounded));
+        assert!(excluded.is_empty());
+    }
+
+    #[test]
+    fn set_operations_work() {
+        let a = IntervalSet::from_iter([0..10, 20..30]);
+        let b = IntervalSet::from(5..25);
+
+        assert_eq!(a.union(&b), IntervalSet::from(0..30));
+        assert_eq!(a.intersection(&b).ranges(), &[5..=9, 20..=24]);
+        assert_eq!(a.difference(&b).ranges(), &[0..=4, 25..=29]);
+        assert_eq!(b.difference(&a), IntervalSet::from(10..20));
+        assert!(a.is_superset(&IntervalSet::from(21..=29)));
+        assert!(!a.is_superset(&b));
+    }
+
+    proptest! {
+        #[test]
+        fn insert_matches_model((ranges, base) in ranges_strategy()) {
+            let mut set = IntervalSet::new();
+            for range in &ranges {
+                set.insert(range.clone());
+            }
+            assert_normalized(&set);
+            prop_assert_eq!(model(&set, base), model_of(&ranges, base));
+            prop_assert_eq!(
+                set.total_len() as usize,
+                model_of(&ranges, base).iter().filter(|flag| **flag).count()
+            );
+        }
+
+        #[test]
+        fn half_open_ranges_match_model((ranges, base) in ranges_strategy()) {
+            // The same values as half-open ranges, as far as their end can be represented
+            let half_open: Vec<Range<i64>> = ranges
+                .iter()
+                .filter_map(|range| Some(*range.start()..range.end().checked_add(1)?))
+                .collect();
+            let inclusive: Vec<_> = half_open.iter().map(|range| range.start..=range.end - 1).collect();
+
+            let set = IntervalSet::from_iter(half_open);
+            prop_assert_eq!(model(&set, base), model_of(&inclusive, base));
+        }
+
+        #[test]
+        fn operations_match_model((a, base) in ranges_strategy(), b_offsets in ranges_strategy()) {
+            // Both sets have to share the model, so the second one is moved to the first's base
+            let (b, b_base) = b_offsets;
+            let b: Vec<_> = b
+                .iter()
+                .map(|range| {
+                    let shift = |value: i64| base + (value - b_base);
+                    shift(*range.start())..=shift(*range.end())
+                })
+                .collect();
+
+            let (set_a, set_b) = (IntervalSet::from_iter(a.clone()), IntervalSet::from_iter(b.clone()));
+            let (model_a, model_b) = (model_of(&a, base), model_of(&b, base));
+            let combine = |op: fn(bool, bool) -> bool| -> Vec<bool> {
+                model_a.iter().zip(&model_b).map(|(x, y)| op(*x, *y)).collect()
+            };
+
+            let union = set_a.union(&set_b);
+            let intersection = set_a.intersection(&set_b);
+            let difference = set_a.difference(&set_b);
+            for set in [&union, &intersection, &difference] {
+                assert_normalized(set);
+            }
+
+            prop_assert_eq!(model(&union, base), combine(|x, y| x || y));
+            prop_assert_eq!(model(&intersection, base), combine(|x, y| x && y));
+            prop_assert_eq!(model(&difference, base), combine(|x, y| x && !y));
+            prop_assert_eq!(
+                set_a.is_superset(&set_b),
+                model_a.iter().zip(&model_b).all(|(x, y)| *x || !*y)
+            );
+        }
+    }
+}
@@ -1,47 +1,31 @@
-use std::{cmp, fs::File, io::Read, ops::RangeInclusive};
+use std::{fs::File, io::Read};
 
-use regex::Regex;
+use day_04::interval_set::IntervalSet;
 
-fn extract_ranges(input: &str) -> (RangeInclusive<usize>, RangeInclusive<usize>) {
-    let re = Regex::new(r"([0-9]+)-([0-9]+),([0-9]+)-([0-9]+)").unwrap();
-
-    let capture = re.captures(input).unwrap();
-
-    let range1 = capture.get(1).unwrap().as_str().parse::<usize>().unwrap()
-        ..=capture.get(2).unwrap().as_str().parse::<usize>().unwrap();
-    let range2 = capture.get(3).unwrap().as_str().parse::<usize>().unwrap()
-        ..=capture.get(4).unwrap().as_str().parse::<usize>().unwrap();
+fn parse_range(input: &str) -> IntervalSet {
+    let (start, end) = input.split_once('-').unwrap();
+    IntervalSet::from(start.parse::<i64>().unwrap()..=end.parse().unwrap())
+}
 
-    (range1, range2)
+fn extract_ranges(input: &str) -> (IntervalSet, IntervalSet) {
+    let (range1, range2) = input.split_once(',').unwrap();
+    (parse_range(range1), parse_range(range2))
 }
 
 fn is_range_fully_contained(input: &str) -> bool {
     let (range1, range2) = extract_ranges(input);
-
-    if range1.start() == range2.start() || range1.end() == range2.end() {
-        return true;
-    }
-
-    let (first_range, second_range) = if range1.start() < range2.start() {
-        (range1, range2)
-    } else {
-        (range2, range1)
-    };
-
-    first_range.end() > second_range.end()
+    range1.is_superset(&range2) || range2.is_superset(&range1)
 }
 
 fn is_range_overlapping(input: &str) -> bool {
     let (range1, range2) = extract_ranges(input);
-
-    // https://stackoverflow.com/a/12888920
-    cmp::max(range1.start(), range2.start()) <= cmp::min(range1.end(), range2.end())
+    !range1.intersection(&range2).is_empty()
 }
 
 fn count_ranges_fully_contained(input: &str) -> usize {
     input
         .trim()
-        .split("\n")
+        .lines()
         .filter(|line| is_range_fully_contained(line.trim()))
         .count()
 }
@@ -49,7 +33,7 @@ fn count_ranges_fully_contained(input: &str) -> usize {
 fn count_overlapping_ranges(input: &str) -> usize {
     input
         .trim()
-        .split("\n")
+        .lines()
         .filter(|line| is_range_overlapping(line.trim()))
         .count()
 }
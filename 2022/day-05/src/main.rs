@@ -1,6 +1,9 @@
 use std::{fs::File, io::Read};
 
 mod parser;
+mod renderer;
+
+use parser::DrawingError;
 
 pub type SupplyStack = Vec<char>;
 
@@ -11,38 +14,51 @@ pub struct Instruction {
     count: usize,
 }
 
+fn execute_instruction(
+    stacks: &mut [SupplyStack],
+    instruction: &Instruction,
+    crane_capacity: usize,
+) {
+    let mut remaining = instruction.count;
+    while remaining > 0 {
+        let lift_count = std::cmp::min(remaining, crane_capacity);
+        let from_stack = &mut stacks[instruction.from - 1];
+        let crane_bay = from_stack.split_off(from_stack.len() - lift_count);
+        stacks[instruction.to - 1].extend(crane_bay);
+        remaining -= lift_count;
+    }
+}
+
 fn execute_instructions(
-    stacks: &mut Vec<SupplyStack>,
-    instructions: &Vec<Instruction>,
+    stacks: &mut [SupplyStack],
+    instructions: &[Instruction],
     crane_capacity: usize,
 ) {
     for instruction in instructions {
-        for i in (0..instruction.count).step_by(crane_capacity) {
-            let mut crane_bay = Vec::new();
-            for _ in 0..std::cmp::min(instruction.count, crane_capacity) {
-                let value = stacks[instruction.from - 1].pop().unwrap();
-                crane_bay.push(value);
-            }
-
-            for value in crane_bay.iter().rev() {
-                stacks[instruction.to - 1].push(*value);
-            }
-        }
+        execute_instruction(stacks, instruction, crane_capacity);
     }
 }
 
-fn get_top_stack_items(stacks: &Vec<SupplyStack>) -> String {
-    stacks
-        .iter()
-        .filter_map(|stack| stack.last())
-        .into_iter()
-        .collect()
+fn get_top_stack_items(stacks: &[SupplyStack]) -> String {
+    stacks.iter().filter_map(|stack| stack.last()).collect()
 }
 
-fn rearrange_stack(input: &str, crane_capacity: usize) -> String {
-    let (mut stacks, instructions) = parser::parse(input);
+fn rearrange_stack(input: &str, crane_capacity: usize) -> Result<String, DrawingError> {
+    let (mut stacks, instructions) = parser::parse(input)?;
     execute_instructions(&mut stacks, &instructions, crane_capacity);
-    get_top_stack_items(&stacks)
+    Ok(get_top_stack_items(&stacks))
+}
+
+/// Prints the stacks after each executed instruction, which is handy to follow what the crane does.
+fn step_through(input: &str, crane_capacity: usize) -> Result<(), DrawingError> {
+    let (mut stacks, instructions) = parser::parse(input)?;
+    println!("{}\n", renderer::render(&stacks));
+
+    for instruction in &instructions {
+        execute_instruction(&mut stacks, instruction, crane_capacity);
+        println!("{}\n{}\n", instruction, renderer::render(&stacks));
+    }
+    Ok(())
 }
 
 fn main() {
@@ -50,8 +66,19 @@ fn main() {
     let mut input = String::new();
     f.read_to_string(&mut input).unwrap();
 
-    println!("Part One: {}", rearrange_stack(&input, 1));
-    println!("Part Two: {}", rearrange_stack(&input, usize::MAX));
+    let args: Vec<_> = std::env::args().collect();
+    if args.iter().any(|arg| arg == "--step") {
+        let crane_capacity = if args.iter().any(|arg| arg == "--part-two") {
+            usize::MAX
+        } else {
+            1
+        };
+        step_through(&input, crane_capacity).unwrap();
+        return;
+    }
+
+    println!("Part One: {}", rearrange_stack(&input, 1).unwrap());
+    println!("Part Two: {}", rearrange_stack(&input, usize::MAX).unwrap());
 }
 
 #[cfg(test)]
@@ -71,9 +98,9 @@ move 2 from 2 to 1
 move 1 from 1 to 2";
 
         let part_one = rearrange_stack(input, 1);
-        assert_eq!(part_one, "CMZ");
+        assert_eq!(part_one, Ok("CMZ".to_string()));
 
         let part_two = rearrange_stack(input, usize::MAX);
-        assert_eq!(part_two, "MCD");
+        assert_eq!(part_two, Ok("MCD".to_string()));
     }
 }
@@ -1,64 +1,127 @@
+use std::ops::Range;
+
 use nom::{
     bytes::complete::tag,
-    character::complete::{anychar, digit1, newline},
-    combinator::opt,
+    character::complete::{digit1, newline},
     multi::separated_list0,
     *,
 };
 
 use crate::{Instruction, SupplyStack};
 
-pub fn parse(input: &str) -> (Vec<SupplyStack>, Vec<Instruction>) {
-    let input_sections: Vec<_> = input.split("\n\n").collect();
+#[derive(Debug, PartialEq)]
+pub enum DrawingError {
+    /// The drawing has no line numbering the stacks.
+    MissingStackNumbers,
+    /// Something other than a number was found in the line of stack numbers. Columns are 1-based.
+    InvalidStackNumber { column: usize },
+    /// The stack numbers are not counting up from 1.
+    UnexpectedStackNumber { expected: usize, found: usize },
+    /// Something other than `[X]` or whitespace was found. Lines and columns are 1-based.
+    MalformedCrate { line: usize, column: usize },
+    /// The crate is not placed underneath any stack number. Lines and columns are 1-based.
+    MisalignedCrate { line: usize, column: usize },
+}
+
+/// Parses the crate drawing and the instructions following it after an empty line. Without
+/// instructions the crates stay where they are.
+pub fn parse(input: &str) -> Result<(Vec<SupplyStack>, Vec<Instruction>), DrawingError> {
+    let (drawing, instructions_section) = input.split_once("\n\n").unwrap_or((input, ""));
 
-    let (_, stacks) = supply_stacks(input_sections[0]).unwrap();
-    let (_, instructions) = instructions(input_sections[1]).unwrap();
+    let stacks = supply_stacks(drawing)?;
+    let (_, instructions) = instructions(instructions_section).unwrap();
 
-    (stacks, instructions)
+    Ok((stacks, instructions))
 }
 
-fn supply_stacks(input: &str) -> IResult<&str, Vec<SupplyStack>> {
-    let lines: Vec<_> = input.split("\n").collect();
-    let mut lines_iter = lines.iter().rev();
+/// Parses the crate drawing. The position of the stacks is taken from the line of stack numbers,
+/// so rows don't need to be padded with trailing whitespace and stack numbers may have multiple
+/// digits. A crate belongs to the stack whose number is written underneath its letter.
+pub fn supply_stacks(input: &str) -> Result<Vec<SupplyStack>, DrawingError> {
+    let lines: Vec<_> = input.lines().collect();
+    let (stack_numbers_line, crate_lines) = lines
+        .split_last()
+        .ok_or(DrawingError::MissingStackNumbers)?;
+
+    let stack_columns = stack_columns(stack_numbers_line)?;
+    let mut stacks: Vec<SupplyStack> = vec![Vec::new(); stack_columns.len()];
+
+    for (line_index, line) in crate_lines.iter().enumerate().rev() {
+        for (column, value) in crate_line(line, line_index)? {
+            let stack_index = stack_columns
+                .iter()
+                .position(|range| range.contains(&column))
+                .ok_or(DrawingError::MisalignedCrate {
+                    line: line_index + 1,
+                    column: column + 1,
+                })?;
+            stacks[stack_index].push(value);
+        }
+    }
 
-    let stack_numbers_line = lines_iter.next().unwrap();
-    let stack_count = stack_numbers_line.split_whitespace().count();
+    Ok(stacks)
+}
 
-    let mut stacks: Vec<SupplyStack> = Vec::with_capacity(stack_count);
-    for _ in 0..stack_count {
-        stacks.push(Vec::new());
-    }
+/// Returns the character range covered by each stack number, in stack order.
+fn stack_columns(line: &str) -> Result<Vec<Range<usize>>, DrawingError> {
+    let mut columns = Vec::new();
+    let mut chars = line.chars().enumerate().peekable();
 
-    for line in lines_iter {
-        let (_, crates) = nom::multi::many1(stack_line)(line)?;
-        for (i, stack_crate) in crates.iter().enumerate() {
-            if let Some(value) = stack_crate {
-                stacks[i].push(*value);
-            }
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut number = c.to_string();
+        while let Some((_, next)) = chars.next_if(|(_, next)| !next.is_whitespace()) {
+            number.push(next);
+        }
+
+        let found = number
+            .parse()
+            .map_err(|_| DrawingError::InvalidStackNumber { column: start + 1 })?;
+        let expected = columns.len() + 1;
+        if found != expected {
+            return Err(DrawingError::UnexpectedStackNumber { expected, found });
         }
+
+        columns.push(start..start + number.chars().count());
     }
 
-    Ok((input, stacks))
-}
+    if columns.is_empty() {
+        return Err(DrawingError::MissingStackNumbers);
+    }
 
-fn stack_line(input: &str) -> IResult<&str, Option<char>> {
-    nom::sequence::terminated(stack_crate, opt(tag(" ")))(input)
+    Ok(columns)
 }
 
-fn stack_crate(input: &str) -> IResult<&str, Option<char>> {
-    nom::branch::alt((filled_crate, empty_crate))(input)
-}
+/// Returns the column of each crate's letter together with the letter itself.
+fn crate_line(line: &str, line_index: usize) -> Result<Vec<(usize, char)>, DrawingError> {
+    let chars: Vec<_> = line.chars().collect();
+    let mut crates = Vec::new();
 
-fn empty_crate(input: &str) -> IResult<&str, Option<char>> {
-    let (input, _) = tag("   ")(input)?;
-    Ok((input, None))
-}
+    let mut column = 0;
+    while column < chars.len() {
+        if chars[column].is_whitespace() {
+            column += 1;
+            continue;
+        }
 
-fn filled_crate(input: &str) -> IResult<&str, Option<char>> {
-    let (input, _) = tag("[")(input)?;
-    let (input, cargo) = anychar(input)?;
-    let (input, _) = tag("]")(input)?;
-    Ok((input, Some(cargo)))
+        match chars[column..] {
+            ['[', value, ']', ..] if !value.is_whitespace() => {
+                crates.push((column + 1, value));
+                column += 3;
+            }
+            _ => {
+                return Err(DrawingError::MalformedCrate {
+                    line: line_index + 1,
+                    column: column + 1,
+                })
+            }
+        }
+    }
+
+    Ok(crates)
 }
 
 fn instructions(input: &str) -> IResult<&str, Vec<Instruction>> {
@@ -96,11 +159,24 @@ move 3 from 1 to 3
 move 2 from 2 to 3
 move 1 from 1 to 2";
 
-        let (stacks, instructions) = parse(input);
+        let (stacks, instructions) = parse(input).unwrap();
         assert_eq!(stacks.len(), 3);
         assert_eq!(instructions.len(), 4);
     }
 
+    #[test]
+    fn parse_errors_are_reported() {
+        assert_eq!(parse("[A]\n 1\n\nmove 1 from 1 to 1").map(|_| ()), Ok(()));
+        assert_eq!(
+            parse("[A]\n[B]\n\nmove 1 from 1 to 1").map(|_| ()),
+            Err(DrawingError::InvalidStackNumber { column: 1 })
+        );
+        assert_eq!(
+            parse("[A]\n 1").map(|(stacks, _)| stacks),
+            Ok(vec![vec!['A']])
+        );
+    }
+
     #[test]
     fn supply_stacks_works() {
         let input = "    [D]    
@@ -108,10 +184,54 @@ move 1 from 1 to 2";
 [Z] [M] [P]
  1   2   3 ";
 
-        let (_, stacks) = supply_stacks(input).unwrap();
+        let stacks = supply_stacks(input).unwrap();
         assert_eq!(stacks.len(), 3);
         assert_eq!(stacks[0].len(), 2);
         assert_eq!(stacks[1].len(), 3);
         assert_eq!(stacks[2].len(), 1);
     }
+
+    #[test]
+    fn supply_stacks_trimmed_lines_works() {
+        let input = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3";
+
+        let stacks = supply_stacks(input).unwrap();
+        assert_eq!(stacks, vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+    }
+
+    #[test]
+    fn supply_stacks_multi_digit_works() {
+        let input = "                                        [K]
+[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [L]
+ 1   2   3   4   5   6   7   8   9   10  11";
+
+        let stacks = supply_stacks(input).unwrap();
+        assert_eq!(stacks.len(), 11);
+        assert_eq!(stacks[9], vec!['J']);
+        assert_eq!(stacks[10], vec!['L', 'K']);
+    }
+
+    #[test]
+    fn supply_stacks_errors_work() {
+        assert_eq!(
+            supply_stacks("  [D]\n 1   2"),
+            Err(DrawingError::MisalignedCrate { line: 1, column: 4 })
+        );
+        assert_eq!(
+            supply_stacks("[D] [E\n 1   2"),
+            Err(DrawingError::MalformedCrate { line: 1, column: 5 })
+        );
+        assert_eq!(
+            supply_stacks(" 1   3"),
+            Err(DrawingError::UnexpectedStackNumber {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            supply_stacks(" 1  x"),
+            Err(DrawingError::InvalidStackNumber { column: 5 })
+        );
+        assert_eq!(supply_stacks(""), Err(DrawingError::MissingStackNumbers));
+    }
 }
@@ -0,0 +1,78 @@
+use std::fmt;
+
+use crate::{Instruction, SupplyStack};
+
+/// Renders the stacks in the same drawing format the puzzle input uses, i.e. one line per
+/// level (highest level first) followed by the line of stack numbers.
+pub fn render(stacks: &[SupplyStack]) -> String {
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+
+    let mut lines: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(value) => format!("[{}]", value),
+                    None => "   ".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+
+    let numbers_line = (1..=stacks.len())
+        .map(|number| format!("{:^3}", number))
+        .collect::<Vec<_>>()
+        .join(" ");
+    lines.push(numbers_line);
+
+    lines.join("\n")
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    const EXAMPLE_STACKS: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+
+    #[test]
+    fn render_works() {
+        let stacks = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
+        assert_eq!(render(&stacks), EXAMPLE_STACKS);
+    }
+
+    #[test]
+    fn render_empty_stacks_works() {
+        let stacks = vec![vec![], vec!['A'], vec![]];
+        assert_eq!(render(&stacks), "    [A]    \n 1   2   3 ");
+    }
+
+    #[test]
+    fn render_roundtrip_works() {
+        let stacks = parser::supply_stacks(EXAMPLE_STACKS).unwrap();
+        let reparsed = parser::supply_stacks(&render(&stacks)).unwrap();
+        assert_eq!(reparsed, stacks);
+
+        let stacks = vec![vec!['A', 'B', 'C', 'D'], vec![], vec!['E'], vec!['F', 'G']];
+        let reparsed = parser::supply_stacks(&render(&stacks)).unwrap();
+        assert_eq!(reparsed, stacks);
+    }
+
+    #[test]
+    fn instruction_display_works() {
+        let instruction = Instruction {
+            from: 2,
+            to: 1,
+            count: 3,
+        };
+        assert_eq!(instruction.to_string(), "move 3 from 2 to 1");
+    }
+}
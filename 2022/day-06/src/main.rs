@@ -1,59 +1,230 @@
-use std::{collections::VecDeque, fs::File, io::Read};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    hash::Hash,
+    io::{self, BufRead, BufReader, Read},
+    time::Instant,
+};
 
-struct StartOfPacketDetector {
+/// Tracks the last `buf_size` pushed items and how many distinct items are among them. A window
+/// of size 0 holds nothing, so it never finds a marker.
+struct StartOfPacketDetector<T> {
     buf_size: usize,
-    char_sequence: VecDeque<char>,
-    char_count: [u8; 26],
+    sequence: VecDeque<T>,
+    counts: HashMap<T, usize>,
 }
 
-impl StartOfPacketDetector {
-    pub fn new(buf_size: usize) -> StartOfPacketDetector {
+impl<T: Hash + Eq + Clone> StartOfPacketDetector<T> {
+    pub fn new(buf_size: usize) -> StartOfPacketDetector<T> {
         StartOfPacketDetector {
             buf_size,
-            char_sequence: VecDeque::with_capacity(buf_size),
-            char_count: [0; 26],
+            sequence: VecDeque::with_capacity(buf_size),
+            counts: HashMap::with_capacity(buf_size),
         }
     }
 
-    pub fn push(&mut self, c: char) {
-        if self.char_sequence.len() == self.buf_size {
-            let popped_char = self.char_sequence.pop_front().unwrap();
-            let popped_index = StartOfPacketDetector::char_to_index(popped_char);
-            self.char_count[popped_index] -= 1;
+    pub fn push(&mut self, item: T) {
+        if self.buf_size == 0 {
+            return;
+        }
+        if self.sequence.len() == self.buf_size {
+            let popped = self.sequence.pop_front().unwrap();
+            let count = self.counts.get_mut(&popped).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&popped);
+            }
         }
 
-        self.char_sequence.push_back(c);
-        let index = StartOfPacketDetector::char_to_index(c);
-        self.char_count[index] += 1;
+        *self.counts.entry(item.clone()).or_insert(0) += 1;
+        self.sequence.push_back(item);
     }
 
+    /// The number of distinct items is the number of keys with a non-zero count, so no rescan of
+    /// the window is needed.
     pub fn marker_found(&self) -> bool {
-        self.char_count.iter().filter(|count| **count > 0).count() == self.buf_size
+        self.buf_size > 0
+            && self.sequence.len() == self.buf_size
+            && self.counts.len() == self.buf_size
     }
+}
+
+/// Returns the position after every window of `buf_size` distinct items, lazily.
+fn find_markers<T, I>(items: I, buf_size: usize) -> impl Iterator<Item = usize>
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+{
+    let mut detector = StartOfPacketDetector::new(buf_size);
+    items.into_iter().enumerate().filter_map(move |(i, item)| {
+        detector.push(item);
+        detector.marker_found().then_some(i + 1)
+    })
+}
 
-    fn char_to_index(c: char) -> usize {
-        ((c as u8) - 97) as usize
+fn find_start_marker(input: &str, buf_size: usize) -> Option<usize> {
+    find_markers(input.chars(), buf_size).next()
+}
+
+/// Consumes the datastream char by char, so the stream never has to fit into memory. Reading
+/// stops as soon as the marker is found; the returned position counts chars, like
+/// [`find_start_marker`] does.
+fn find_start_marker_in_stream<R: BufRead>(
+    reader: R,
+    buf_size: usize,
+) -> io::Result<Option<usize>> {
+    Ok(find_start_markers_in_stream(reader, &[buf_size])?[0])
+}
+
+/// Like [`find_start_marker_in_stream`] for several window sizes at once, so a stream which can
+/// only be read once, like stdin, is searched in a single pass. Reading stops as soon as every
+/// marker is found.
+fn find_start_markers_in_stream<R: BufRead>(
+    reader: R,
+    buf_sizes: &[usize],
+) -> io::Result<Vec<Option<usize>>> {
+    let mut detectors: Vec<_> = buf_sizes
+        .iter()
+        .map(|buf_size| StartOfPacketDetector::new(*buf_size))
+        .collect();
+    let mut markers = vec![None; buf_sizes.len()];
+    for (i, c) in utf8_chars(reader).enumerate() {
+        let c = c?;
+        for (detector, marker) in detectors.iter_mut().zip(&mut markers) {
+            if marker.is_none() {
+                detector.push(c);
+                if detector.marker_found() {
+                    *marker = Some(i + 1);
+                }
+            }
+        }
+        if markers.iter().all(Option::is_some) {
+            break;
+        }
     }
+    Ok(markers)
 }
 
-fn find_start_marker(input: &str, buf_size: usize) -> usize {
-    let mut detector = StartOfPacketDetector::new(buf_size);
-    for (i, c) in input.chars().enumerate() {
-        detector.push(c);
-        if detector.marker_found() {
-            return i + 1;
+/// Decodes the chars of a UTF-8 stream one at a time. Invalid or truncated sequences are reported
+/// as [`io::ErrorKind::InvalidData`].
+fn utf8_chars<R: BufRead>(reader: R) -> impl Iterator<Item = io::Result<char>> {
+    let mut bytes = reader.bytes();
+    std::iter::from_fn(move || {
+        let mut encoded = [0; 4];
+        encoded[0] = match bytes.next()? {
+            Ok(byte) => byte,
+            Err(err) => return Some(Err(err)),
+        };
+        // The leading ones of the first byte give the length of a multibyte sequence; any count
+        // other than 2 to 4 is invalid and left to fail decoding
+        let len = match encoded[0].leading_ones() {
+            0 => 1,
+            count @ 2..=4 => count as usize,
+            _ => 0,
+        };
+        for byte in &mut encoded[1..len.max(1)] {
+            match bytes.next() {
+                Some(Ok(next)) => *byte = next,
+                Some(Err(err)) => return Some(Err(err)),
+                None => break,
+            }
+        }
+
+        let c = std::str::from_utf8(&encoded[..len])
+            .ok()
+            .and_then(|decoded| decoded.chars().next());
+        Some(c.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid UTF-8")))
+    })
+}
+
+/// Generates `len` bytes cycling through `abc` followed by a 14 byte marker, without ever holding
+/// the datastream in memory. Streams shorter than the marker are its first `len` bytes.
+struct GeneratedStream {
+    len: usize,
+    position: usize,
+}
+
+impl GeneratedStream {
+    const MARKER: &'static [u8] = b"abcdefghijklmn";
+
+    fn new(len: usize) -> GeneratedStream {
+        GeneratedStream { len, position: 0 }
+    }
+
+    fn byte_at(&self, position: usize) -> u8 {
+        let marker_start = self.len.saturating_sub(GeneratedStream::MARKER.len());
+        if position >= marker_start {
+            GeneratedStream::MARKER[position - marker_start]
+        } else {
+            b"abc"[position % 3]
         }
     }
-    panic!()
 }
 
-fn main() {
-    let mut f = File::open("input.txt").unwrap();
+impl Read for GeneratedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = std::cmp::min(buf.len(), self.len - self.position);
+        for (offset, byte) in buf[..count].iter_mut().enumerate() {
+            *byte = self.byte_at(self.position + offset);
+        }
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// Compares the in-memory and the streaming detection on a generated datastream of `megabytes`.
+fn bench(megabytes: usize) {
+    let len = megabytes * 1024 * 1024;
+
+    let start = Instant::now();
     let mut input = String::new();
-    f.read_to_string(&mut input).unwrap();
+    GeneratedStream::new(len)
+        .read_to_string(&mut input)
+        .unwrap();
+    let marker = find_start_marker(&input, 14);
+    println!("In-memory: {:?} after {:?}", marker, start.elapsed());
+    drop(input);
+
+    let start = Instant::now();
+    let reader = BufReader::new(GeneratedStream::new(len));
+    let marker = find_start_marker_in_stream(reader, 14).unwrap();
+    println!("Streaming: {:?} after {:?}", marker, start.elapsed());
+}
+
+fn print_marker(name: &str, marker: Option<usize>) {
+    match marker {
+        Some(position) => println!("{}: {}", name, position),
+        None => println!("{}: no marker found", name),
+    }
+}
+
+/// Usage: `day-06 [--stream <path or - for stdin>] [--bench <megabytes>]`. `--stream` searches
+/// for both parts' markers in a single pass, as stdin can only be read once.
+fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["--bench", megabytes] => bench(megabytes.parse().unwrap()),
+        ["--stream", path] => {
+            let markers = match path {
+                "-" => find_start_markers_in_stream(io::stdin().lock(), &[4, 14]),
+                path => find_start_markers_in_stream(
+                    BufReader::new(File::open(path).unwrap()),
+                    &[4, 14],
+                ),
+            }
+            .unwrap();
+            print_marker("Part One", markers[0]);
+            print_marker("Part Two", markers[1]);
+        }
+        _ => {
+            let mut f = File::open("input.txt").unwrap();
+            let mut input = String::new();
+            f.read_to_string(&mut input).unwrap();
 
-    println!("Part One: {}", find_start_marker(&input, 4));
-    println!("Part Two: {}", find_start_marker(&input, 14));
+            print_marker("Part One", find_start_marker(&input, 4));
+            print_marker("Part Two", find_start_marker(&input, 14));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -65,10 +236,10 @@ mod tests {
         let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
 
         let part_one = find_start_marker(input, 4);
-        assert_eq!(part_one, 7);
+        assert_eq!(part_one, Some(7));
 
         let part_two = find_start_marker(input, 14);
-        assert_eq!(part_two, 19);
+        assert_eq!(part_two, Some(19));
     }
 
     #[test]
@@ -76,10 +247,10 @@ mod tests {
         let input = "bvwbjplbgvbhsrlpgdmjqwftvncz";
 
         let part_one = find_start_marker(input, 4);
-        assert_eq!(part_one, 5);
+        assert_eq!(part_one, Some(5));
 
         let part_two = find_start_marker(input, 14);
-        assert_eq!(part_two, 23);
+        assert_eq!(part_two, Some(23));
     }
 
     #[test]
@@ -87,10 +258,10 @@ mod tests {
         let input = "nppdvjthqldpwncqszvftbrmjlhg";
 
         let part_one = find_start_marker(input, 4);
-        assert_eq!(part_one, 6);
+        assert_eq!(part_one, Some(6));
 
         let part_two = find_start_marker(input, 14);
-        assert_eq!(part_two, 23);
+        assert_eq!(part_two, Some(23));
     }
 
     #[test]
@@ -98,10 +269,10 @@ mod tests {
         let input = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
 
         let part_one = find_start_marker(input, 4);
-        assert_eq!(part_one, 10);
+        assert_eq!(part_one, Some(10));
 
         let part_two = find_start_marker(input, 14);
-        assert_eq!(part_two, 29);
+        assert_eq!(part_two, Some(29));
     }
 
     #[test]
@@ -109,9 +280,104 @@ mod tests {
         let input = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
 
         let part_one = find_start_marker(input, 4);
-        assert_eq!(part_one, 11);
+        assert_eq!(part_one, Some(11));
 
         let part_two = find_start_marker(input, 14);
-        assert_eq!(part_two, 26);
+        assert_eq!(part_two, Some(26));
+    }
+
+    #[test]
+    fn any_alphabet_works() {
+        assert_eq!(find_start_marker("AAbB1", 4), Some(5));
+        assert_eq!(find_start_marker("ääöüß", 4), Some(5));
+        assert_eq!(
+            find_markers([1, 2, 2, 3, 4, 5], 3).collect::<Vec<_>>(),
+            vec![5, 6]
+        );
+    }
+
+    #[test]
+    fn all_markers_works() {
+        assert_eq!(
+            find_markers("abcabca".chars(), 3).collect::<Vec<_>>(),
+            vec![3, 4, 5, 6, 7]
+        );
+        assert_eq!(
+            find_markers("aabbcc".chars(), 3).collect::<Vec<_>>(),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn no_marker_works() {
+        assert_eq!(find_start_marker("aaaaaaaa", 4), None);
+        assert_eq!(find_start_marker("abc", 4), None);
+        assert_eq!(find_start_marker("abc", 0), None);
+        assert_eq!(
+            find_start_marker_in_stream("abc".as_bytes(), 0).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn stream_works() {
+        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
+        let part_one = find_start_marker_in_stream(input.as_bytes(), 4).unwrap();
+        assert_eq!(part_one, Some(7));
+
+        let part_two = find_start_marker_in_stream(input.as_bytes(), 14).unwrap();
+        assert_eq!(part_two, Some(19));
+
+        let none = find_start_marker_in_stream("aaaa".as_bytes(), 4).unwrap();
+        assert_eq!(none, None);
+
+        let both = find_start_markers_in_stream(input.as_bytes(), &[4, 14]).unwrap();
+        assert_eq!(both, vec![Some(7), Some(19)]);
+        let partial = find_start_markers_in_stream("abcd".as_bytes(), &[4, 14]).unwrap();
+        assert_eq!(partial, vec![Some(4), None]);
+    }
+
+    #[test]
+    fn stream_matches_in_memory_for_multibyte_chars() {
+        for input in ["ääöüß", "aäaäböc€d", "€€€𝄞€ab𝄞c"] {
+            for buf_size in [2, 3, 4] {
+                let streamed = find_start_marker_in_stream(input.as_bytes(), buf_size).unwrap();
+                assert_eq!(streamed, find_start_marker(input, buf_size), "{input}");
+            }
+        }
+        assert_eq!(
+            find_start_marker_in_stream("ääöüß".as_bytes(), 4).unwrap(),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_fails() {
+        for input in [&b"ab\xffcd"[..], b"ab\xc3", b"\x80abcd"] {
+            let err = find_start_marker_in_stream(input, 4).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn generated_stream_works() {
+        let mut input = String::new();
+        GeneratedStream::new(1000)
+            .read_to_string(&mut input)
+            .unwrap();
+        assert_eq!(input.len(), 1000);
+
+        let reader = BufReader::new(GeneratedStream::new(1000));
+        let streamed = find_start_marker_in_stream(reader, 14).unwrap();
+        assert_eq!(streamed, find_start_marker(&input, 14));
+        assert_eq!(streamed, Some(1000));
+
+        // Shorter than the marker, so no marker of 14 bytes fits
+        let mut short = String::new();
+        GeneratedStream::new(5).read_to_string(&mut short).unwrap();
+        assert_eq!(short, "abcde");
+        assert_eq!(find_start_marker(&short, 14), None);
+        assert!(GeneratedStream::new(0).read_to_string(&mut short).is_ok());
     }
 }
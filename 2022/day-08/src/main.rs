@@ -1,166 +1,99 @@
-use std::{cmp::max, fs::File, io::Read};
+use std::{fs::File, io::Read, path::Path};
+
+#[cfg(test)]
+mod naive;
+mod render;
 
 type Forrest = Vec<TreeRow>;
 type TreeRow = Vec<u8>;
 
-fn vertical_iterator<'a>(
-    forrest: &'a Forrest,
-    column: usize,
-    top_to_bottom: bool,
-) -> Box<dyn Iterator<Item = &'a u8> + 'a> {
-    if top_to_bottom {
-        Box::new(
-            (0..forrest[0].len())
-                .into_iter()
-                .map(move |row| &forrest[row][column]),
-        )
-    } else {
-        Box::new(
-            (0..forrest[0].len())
-                .rev()
-                .into_iter()
-                .map(move |row| &forrest[row][column]),
-        )
-    }
-}
-
-fn visible_tree_indexes(tree_row_iter: impl Iterator<Item = u8>) -> Vec<usize> {
-    let mut visible_indexes = vec![];
-
-    let mut highest_tree: u8 = 0;
-    for (index, tree_height) in tree_row_iter.enumerate() {
-        if tree_height > highest_tree || index == 0 {
-            highest_tree = tree_height;
-            visible_indexes.push(index);
-        }
-    }
-
-    visible_indexes
+/// Visibility from outside the forrest and scenic score of every tree.
+struct ForrestView {
+    visible: Vec<Vec<bool>>,
+    scenic_scores: Vec<Vec<usize>>,
 }
 
-fn count_visible_trees(input: &str) -> usize {
-    let forrest = read_forrest(input);
-    let mut visible_tree_positions: Vec<(usize, usize)> = Vec::new();
-
-    let rows_count = forrest.len();
-    let cols_count = forrest[0].len();
-
-    println!("Processing forrest with {rows_count} rows and {cols_count} columns...");
-
-    // Horizontal first
-    for row in 0..rows_count {
-        let mut ltr: Vec<(usize, usize)> = visible_tree_indexes(forrest[row].iter().cloned())
-            .iter()
-            .map(|index| (*index, row))
-            .collect();
-        visible_tree_positions.append(&mut ltr);
-
-        let mut rtl: Vec<(usize, usize)> = visible_tree_indexes(forrest[row].iter().rev().cloned())
-            .iter()
-            .map(|index| (cols_count - *index - 1, row))
-            .collect();
-        visible_tree_positions.append(&mut rtl);
-    }
-
-    // Vertical second
-    for column in 0..cols_count {
+/// Looks along a single line of trees, given by their positions. A stack of the trees that are
+/// not yet blocked by a higher one is kept: for each tree all lower trees are popped, what is left
+/// on top is the tree blocking the view. If nothing is left, the view reaches the edge and the
+/// tree is visible from there. Every tree is pushed and popped once, so a line takes O(length).
+fn look_along(
+    forrest: &Forrest,
+    line: impl Iterator<Item = (usize, usize)>,
+    view: &mut ForrestView,
+) {
+    let mut stack: Vec<(usize, u8)> = Vec::new();
+
+    for (index, (row, col)) in line.enumerate() {
+        let tree_height = forrest[row][col];
+        while stack
+            .last()
+            .is_some_and(|(_, height)| *height < tree_height)
         {
-            let ttb_iter = vertical_iterator(&forrest, column, true).cloned();
-            let mut ttb: Vec<(usize, usize)> = visible_tree_indexes(ttb_iter)
-                .iter()
-                .map(|index| (column, *index))
-                .collect();
-            visible_tree_positions.append(&mut ttb);
+            stack.pop();
         }
 
-        let btt_iter = vertical_iterator(&forrest, column, false).cloned();
-        let mut btt: Vec<(usize, usize)> = visible_tree_indexes(btt_iter)
-            .iter()
-            .map(|index| (column, rows_count - *index - 1))
-            .collect();
-        visible_tree_positions.append(&mut btt);
-    }
-
-    visible_tree_positions.sort();
-    visible_tree_positions.dedup();
-
-    visible_tree_positions.len()
-}
-
-fn make_iter_from(
-    forrest: &Forrest,
-    start: (usize, usize),
-    direction: (i32, i32),
-) -> impl Iterator<Item = u8> + '_ {
-    struct ForrestIter<'a> {
-        forrest: &'a Forrest,
-        pos: (i32, i32),
-        direction: (i32, i32),
-    }
-
-    impl<'a> Iterator for ForrestIter<'a> {
-        type Item = u8;
-
-        fn next(&mut self) -> Option<Self::Item> {
-            self.pos.0 += self.direction.0;
-            self.pos.1 += self.direction.1;
-
-            if self.pos.0 < 0 || self.pos.1 < 0 {
-                return None;
+        let viewing_distance = match stack.last() {
+            Some((blocking_index, _)) => index - blocking_index,
+            None => {
+                view.visible[row][col] = true;
+                index
             }
+        };
+        view.scenic_scores[row][col] *= viewing_distance;
 
-            self.forrest
-                .get(max(0, self.pos.0) as usize)
-                .and_then(|tree_row| tree_row.get(max(0, self.pos.1) as usize).copied())
-        }
-    }
-
-    ForrestIter {
-        forrest,
-        pos: (start.0 as i32, start.1 as i32),
-        direction,
+        stack.push((index, tree_height));
     }
 }
 
-fn calc_scenic_score(forrest: &Forrest, row: usize, col: usize) -> usize {
+fn view_forrest(forrest: &Forrest) -> ForrestView {
     let rows_count = forrest.len();
-    let cols_count = forrest[0].len();
-    let tree_hight = forrest[row][col];
+    let cols_count = forrest.first().map_or(0, |tree_row| tree_row.len());
 
-    let score_right = make_iter_from(forrest, (row, col), (0, 1))
-        .position(|candidate_hight| candidate_hight >= tree_hight)
-        .map_or_else(|| cols_count - col - 1, |pos| pos + 1);
+    let mut view = ForrestView {
+        visible: vec![vec![false; cols_count]; rows_count],
+        scenic_scores: vec![vec![1; cols_count]; rows_count],
+    };
 
-    let score_left = make_iter_from(forrest, (row, col), (0, -1))
-        .position(|candidate_hight| candidate_hight >= tree_hight)
-        .map_or_else(|| col, |pos| pos + 1);
-
-    let score_top = make_iter_from(forrest, (row, col), (-1, 0))
-        .position(|candidate_hight| candidate_hight >= tree_hight)
-        .map_or_else(|| row, |pos| pos + 1);
+    for row in 0..rows_count {
+        look_along(forrest, (0..cols_count).map(|col| (row, col)), &mut view);
+        look_along(
+            forrest,
+            (0..cols_count).rev().map(|col| (row, col)),
+            &mut view,
+        );
+    }
+    for col in 0..cols_count {
+        look_along(forrest, (0..rows_count).map(|row| (row, col)), &mut view);
+        look_along(
+            forrest,
+            (0..rows_count).rev().map(|row| (row, col)),
+            &mut view,
+        );
+    }
 
-    let score_bottom = make_iter_from(forrest, (row, col), (1, 0))
-        .position(|candidate_hight| candidate_hight >= tree_hight)
-        .map_or_else(|| rows_count - row - 1, |pos| pos + 1);
+    view
+}
 
-    score_right * score_left * score_top * score_bottom
+fn count_visible_trees(input: &str) -> usize {
+    let forrest = read_forrest(input);
+    view_forrest(&forrest)
+        .visible
+        .iter()
+        .flatten()
+        .filter(|visible| **visible)
+        .count()
 }
 
 fn highest_scenic_score(input: &str) -> usize {
     let forrest = read_forrest(input);
-
-    let rows_count = forrest.len();
-    let cols_count = forrest[0].len();
-
-    let mut max_score: usize = 0;
-    for row in 0..rows_count {
-        for col in 0..cols_count {
-            let score = calc_scenic_score(&forrest, row, col);
-            max_score = max(score, max_score);
-        }
-    }
-
-    max_score
+    view_forrest(&forrest)
+        .scenic_scores
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(0)
 }
 
 fn read_tree_row(input: &str) -> TreeRow {
@@ -168,13 +101,11 @@ fn read_tree_row(input: &str) -> TreeRow {
 }
 
 fn read_forrest(input: &str) -> Forrest {
-    input
-        .trim()
-        .split("\n")
-        .map(|line| read_tree_row(line))
-        .collect()
+    input.trim().lines().map(read_tree_row).collect()
 }
 
+/// Usage: `day-08 [--visibility] [--heatmap] [--no-color] [--top <k>]
+/// [--visibility-image <path>] [--heatmap-image <path>]`, images are written as `.png` or `.ppm`.
 fn main() {
     let mut f = File::open("input.txt").unwrap();
     let mut input = String::new();
@@ -182,54 +113,78 @@ fn main() {
 
     println!("Part One: {}", count_visible_trees(&input));
     println!("Part Two: {}", highest_scenic_score(&input));
+
+    let forrest = read_forrest(&input);
+    let view = view_forrest(&forrest);
+
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let ansi = !args.iter().any(|arg| arg == "--no-color");
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--visibility" => println!("\n{}", render::visibility_mask(&forrest, &view, ansi)),
+            "--heatmap" => println!("\n{}", render::scenic_heatmap(&forrest, &view, ansi)),
+            "--top" => {
+                let k = args_iter.next().unwrap().parse().unwrap();
+                for ((row, col), score) in render::top_scenic_trees(&view, k) {
+                    println!("Row {row}, column {col}: {score}");
+                }
+            }
+            "--visibility-image" => {
+                let path = Path::new(args_iter.next().unwrap());
+                render::write_image(path, &view, render::MapKind::Visibility, 4);
+            }
+            "--heatmap-image" => {
+                let path = Path::new(args_iter.next().unwrap());
+                render::write_image(path, &view, render::MapKind::Heatmap, 4);
+            }
+            _ => {}
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::*;
 
-    #[test]
-    fn vertical_iterator_works() {
-        let forrest = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
-
-        let mut iter_ttb = vertical_iterator(&forrest, 1, true);
-        assert_eq!(iter_ttb.next(), Some(&2));
-        assert_eq!(iter_ttb.next(), Some(&5));
-        assert_eq!(iter_ttb.next(), Some(&8));
-        assert_eq!(iter_ttb.next(), None);
-
-        let mut iter_btt = vertical_iterator(&forrest, 2, false);
-        assert_eq!(iter_btt.next(), Some(&9));
-        assert_eq!(iter_btt.next(), Some(&6));
-        assert_eq!(iter_btt.next(), Some(&3));
-        assert_eq!(iter_btt.next(), None);
+    /// Small xorshift generator, good enough to produce reproducible random forrests.
+    fn random_forrest(seed: &mut u64, rows_count: usize, cols_count: usize) -> Forrest {
+        let mut next = || {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            *seed
+        };
+
+        (0..rows_count)
+            .map(|_| (0..cols_count).map(|_| (next() % 10) as u8).collect())
+            .collect()
     }
 
     #[test]
-    fn calc_scenic_score_works() {
-        let vertical_forrest = vec![
-            vec![1, 1, 1, 1, 1, 1, 1],
-            vec![1, 2, 1, 3, 2, 4, 1],
-            vec![1, 1, 1, 1, 1, 1, 1],
-        ];
-
-        assert_eq!(calc_scenic_score(&vertical_forrest, 1, 1), 2);
-        assert_eq!(calc_scenic_score(&vertical_forrest, 1, 5), 5);
-        assert_eq!(calc_scenic_score(&vertical_forrest, 1, 0), 0);
-        assert_eq!(calc_scenic_score(&vertical_forrest, 1, 6), 0);
-
-        let horizontal_forrest = vec![
-            vec![1, 1, 1],
-            vec![1, 3, 1],
-            vec![1, 7, 1],
-            vec![1, 6, 1],
-            vec![1, 1, 1],
-        ];
-
-        assert_eq!(calc_scenic_score(&horizontal_forrest, 1, 1), 1);
-        assert_eq!(calc_scenic_score(&horizontal_forrest, 2, 1), 4);
-        assert_eq!(calc_scenic_score(&horizontal_forrest, 0, 1), 0);
-        assert_eq!(calc_scenic_score(&horizontal_forrest, 4, 1), 0);
+    fn view_forrest_matches_naive() {
+        let mut seed = 0x2022_0008;
+        for rows_count in 1..12 {
+            for cols_count in 1..12 {
+                let forrest = random_forrest(&mut seed, rows_count, cols_count);
+                let view = view_forrest(&forrest);
+
+                let visible_count = view.visible.iter().flatten().filter(|v| **v).count();
+                assert_eq!(visible_count, naive::count_visible_trees(&forrest));
+
+                let highest_score = view.scenic_scores.iter().flatten().max().copied();
+                assert_eq!(highest_score, Some(naive::highest_scenic_score(&forrest)));
+
+                for row in 0..rows_count {
+                    for col in 0..cols_count {
+                        assert_eq!(
+                            view.scenic_scores[row][col],
+                            naive::calc_scenic_score(&forrest, row, col)
+                        );
+                    }
+                }
+            }
+        }
     }
 
     #[test]
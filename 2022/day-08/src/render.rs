@@ -0,0 +1,229 @@
+use std::{fs::File, io::BufWriter, io::Write, path::Path};
+
+use crate::{Forrest, ForrestView};
+
+type Colour = [u8; 3];
+
+const VISIBLE_COLOUR: Colour = [46, 160, 67];
+const HIDDEN_COLOUR: Colour = [48, 48, 48];
+
+/// Scenic scores are spread over several orders of magnitude, so the square root of the relative
+/// score is used to make the lower scores distinguishable. Goes from black over red to yellow.
+fn heat_colour(score: usize, max_score: usize) -> Colour {
+    let ratio = if max_score == 0 {
+        0.0
+    } else {
+        (score as f64 / max_score as f64).sqrt()
+    };
+
+    let red = (ratio * 2.0).min(1.0);
+    let green = (ratio * 2.0 - 1.0).max(0.0);
+    [(red * 255.0) as u8, (green * 255.0) as u8, 0]
+}
+
+fn visibility_colours(view: &ForrestView) -> Vec<Vec<Colour>> {
+    view.visible
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|visible| match visible {
+                    true => VISIBLE_COLOUR,
+                    false => HIDDEN_COLOUR,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn heatmap_colours(view: &ForrestView) -> Vec<Vec<Colour>> {
+    let max_score = view
+        .scenic_scores
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(0);
+    view.scenic_scores
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|score| heat_colour(*score, max_score))
+                .collect()
+        })
+        .collect()
+}
+
+/// Prints every tree's height, with the background coloured by `colours`.
+fn ansi_map(forrest: &Forrest, colours: &[Vec<Colour>]) -> String {
+    forrest
+        .iter()
+        .zip(colours)
+        .map(|(tree_row, colour_row)| {
+            let mut line: String = tree_row
+                .iter()
+                .zip(colour_row)
+                .map(|(height, [r, g, b])| format!("\x1b[48;2;{r};{g};{b}m{height}"))
+                .collect();
+            line.push_str("\x1b[0m");
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Visible trees as `#`, hidden ones as `.`, optionally showing the heights in colour instead.
+pub fn visibility_mask(forrest: &Forrest, view: &ForrestView, ansi: bool) -> String {
+    if ansi {
+        return ansi_map(forrest, &visibility_colours(view));
+    }
+
+    view.visible
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|visible| if *visible { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Every tree's scenic score on a scale from `0` to `9` relative to the highest score, optionally
+/// showing the heights on a coloured background instead.
+pub fn scenic_heatmap(forrest: &Forrest, view: &ForrestView, ansi: bool) -> String {
+    if ansi {
+        return ansi_map(forrest, &heatmap_colours(view));
+    }
+
+    let max_score = view
+        .scenic_scores
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(0);
+    view.scenic_scores
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|score| match max_score {
+                    0 => '0',
+                    _ => char::from_digit((score * 9 / max_score) as u32, 10).unwrap(),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The `k` trees with the highest scenic score as `((row, column), score)`, highest first.
+pub fn top_scenic_trees(view: &ForrestView, k: usize) -> Vec<((usize, usize), usize)> {
+    let mut trees: Vec<_> = view
+        .scenic_scores
+        .iter()
+        .enumerate()
+        .flat_map(|(row, scores)| {
+            scores
+                .iter()
+                .enumerate()
+                .map(move |(col, score)| ((row, col), *score))
+        })
+        .collect();
+
+    trees.sort_by(|(a_pos, a_score), (b_pos, b_score)| b_score.cmp(a_score).then(a_pos.cmp(b_pos)));
+    trees.truncate(k);
+    trees
+}
+
+pub enum MapKind {
+    Visibility,
+    Heatmap,
+}
+
+/// Writes the map as an image with `scale`×`scale` pixels per tree. The format is chosen by the
+/// file extension, `.png` or `.ppm`.
+pub fn write_image(path: &Path, view: &ForrestView, kind: MapKind, scale: usize) {
+    let colours = match kind {
+        MapKind::Visibility => visibility_colours(view),
+        MapKind::Heatmap => heatmap_colours(view),
+    };
+
+    let height = colours.len() * scale;
+    let width = colours.first().map_or(0, |row| row.len()) * scale;
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for colour_row in &colours {
+        for _ in 0..scale {
+            for colour in colour_row {
+                for _ in 0..scale {
+                    pixels.extend_from_slice(colour);
+                }
+            }
+        }
+    }
+
+    let file = BufWriter::new(File::create(path).unwrap());
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => write_png(file, width, height, &pixels),
+        Some("ppm") => write_ppm(file, width, height, &pixels),
+        _ => panic!("Unsupported image format: {}", path.display()),
+    }
+}
+
+fn write_ppm(mut out: impl Write, width: usize, height: usize, pixels: &[u8]) {
+    write!(out, "P6\n{width} {height}\n255\n").unwrap();
+    out.write_all(pixels).unwrap();
+}
+
+fn write_png(out: impl Write, width: usize, height: usize, pixels: &[u8]) {
+    let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(pixels).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_forrest, view_forrest};
+
+    const EXAMPLE_INPUT: &str = "30373
+25512
+65332
+33549
+35390";
+
+    #[test]
+    fn visibility_mask_works() {
+        let forrest = read_forrest(EXAMPLE_INPUT);
+        let view = view_forrest(&forrest);
+
+        let mask = visibility_mask(&forrest, &view, false);
+        assert_eq!(mask, "#####\n###.#\n##.##\n#.#.#\n#####");
+    }
+
+    #[test]
+    fn scenic_heatmap_works() {
+        let forrest = read_forrest(EXAMPLE_INPUT);
+        let view = view_forrest(&forrest);
+
+        let heatmap = scenic_heatmap(&forrest, &view, false);
+        assert_eq!(heatmap, "00000\n01410\n06120\n01930\n00000");
+    }
+
+    #[test]
+    fn top_scenic_trees_works() {
+        let forrest = read_forrest(EXAMPLE_INPUT);
+        let view = view_forrest(&forrest);
+
+        let top = top_scenic_trees(&view, 2);
+        assert_eq!(top, vec![((3, 2), 8), ((2, 1), 6)]);
+    }
+
+    #[test]
+    fn write_ppm_works() {
+        let mut out = Vec::new();
+        write_ppm(&mut out, 1, 1, &[1, 2, 3]);
+        assert_eq!(out, b"P6\n1 1\n255\n\x01\x02\x03");
+    }
+}
@@ -1,52 +1,74 @@
-use std::{fs::File, io::Read};
-
-#[derive(Copy, Clone)]
-enum HeadMovement {
-    UP,
-    DOWN,
-    LEFT,
-    RIGHT,
+use std::{fs::File, io::Read, path::Path, time::Duration};
+
+mod render;
+mod rope;
+
+use rope::Rope;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HeadMovement {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
-type Position = (i64, i64);
+/// A movement repeated `count` times, e.g. `R 4`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Motion {
+    pub movement: HeadMovement,
+    pub count: usize,
+}
+
+pub type Position = (i64, i64);
 
-fn parse_input_line(input: &str) -> Vec<HeadMovement> {
-    let parts: Vec<_> = input.trim().split_whitespace().collect();
+fn parse_input_line(input: &str) -> Motion {
+    let parts: Vec<_> = input.split_whitespace().collect();
 
     let movement = match parts[0] {
-        "U" => HeadMovement::UP,
-        "D" => HeadMovement::DOWN,
-        "L" => HeadMovement::LEFT,
-        "R" => HeadMovement::RIGHT,
+        "U" => HeadMovement::Up,
+        "D" => HeadMovement::Down,
+        "L" => HeadMovement::Left,
+        "R" => HeadMovement::Right,
+        "UL" => HeadMovement::UpLeft,
+        "UR" => HeadMovement::UpRight,
+        "DL" => HeadMovement::DownLeft,
+        "DR" => HeadMovement::DownRight,
         _ => panic!(),
     };
     let count: usize = parts[1].parse().unwrap();
 
-    [movement].repeat(count)
+    Motion { movement, count }
 }
 
-fn parse_input(input: &str) -> Vec<HeadMovement> {
-    input
-        .trim()
-        .split("\n")
-        .flat_map(|line| parse_input_line(line))
-        .collect()
+fn parse_input(input: &str) -> Vec<Motion> {
+    input.trim().lines().map(parse_input_line).collect()
 }
 
 fn move_head(head_pos: &mut Position, movement: &HeadMovement) {
-    match movement {
-        HeadMovement::UP => head_pos.1 = head_pos.1 + 1,
-        HeadMovement::DOWN => head_pos.1 = head_pos.1 - 1,
-        HeadMovement::LEFT => head_pos.0 = head_pos.0 - 1,
-        HeadMovement::RIGHT => head_pos.0 = head_pos.0 + 1,
-    }
+    let (x_step, y_step) = match movement {
+        HeadMovement::Up => (0, 1),
+        HeadMovement::Down => (0, -1),
+        HeadMovement::Left => (-1, 0),
+        HeadMovement::Right => (1, 0),
+        HeadMovement::UpLeft => (-1, 1),
+        HeadMovement::UpRight => (1, 1),
+        HeadMovement::DownLeft => (-1, -1),
+        HeadMovement::DownRight => (1, -1),
+    };
+
+    head_pos.0 += x_step;
+    head_pos.1 += y_step;
 }
 
+/// Points are neighbours if they touch, including diagonally, i.e. their Chebyshev distance is
+/// at most one.
 fn is_neighboring_point(p1: &Position, p2: &Position) -> bool {
-    let x_diff = p1.0 - p2.0;
-    let y_diff = p1.1 - p2.1;
-
-    f64::sqrt((x_diff * x_diff + y_diff * y_diff) as f64) < 2.
+    (p1.0 - p2.0).abs() <= 1 && (p1.1 - p2.1).abs() <= 1
 }
 
 fn move_tail(head_pos: &Position, tail_pos: &Position) -> Option<Position> {
@@ -54,72 +76,85 @@ fn move_tail(head_pos: &Position, tail_pos: &Position) -> Option<Position> {
         return None;
     }
 
-    let mut moved_tail_pos = tail_pos.clone();
-
-    if head_pos.0 > tail_pos.0 {
-        moved_tail_pos.0 += 1;
-    } else if head_pos.0 < tail_pos.0 {
-        moved_tail_pos.0 -= 1;
-    }
-
-    if head_pos.1 > tail_pos.1 {
-        moved_tail_pos.1 += 1;
-    } else if head_pos.1 < tail_pos.1 {
-        moved_tail_pos.1 -= 1;
-    }
-
-    Some(moved_tail_pos)
-}
-
-fn simulate_rope_positions(
-    head_movements: &Vec<HeadMovement>,
-    mut rope: Vec<Position>,
-) -> Vec<Position> {
-    let mut tail_positions = vec![rope.last().unwrap().clone()];
-
-    for movement in head_movements {
-        move_head(rope.first_mut().unwrap(), movement);
-
-        for head_index in 0..rope.len() - 1 {
-            if let Some(moved_tail_pos) = move_tail(&rope[head_index], &rope[head_index + 1]) {
-                rope[head_index + 1] = moved_tail_pos;
-            }
-        }
-
-        tail_positions.push(rope.last().unwrap().clone());
-    }
-
-    tail_positions
-}
-
-fn count_unique_tail_positions(head_movements: &Vec<HeadMovement>, rope: Vec<Position>) -> usize {
-    let mut tail_positions = simulate_rope_positions(&head_movements, rope);
-
-    tail_positions.sort();
-    tail_positions.dedup();
-    tail_positions.len()
+    Some((
+        tail_pos.0 + (head_pos.0 - tail_pos.0).signum(),
+        tail_pos.1 + (head_pos.1 - tail_pos.1).signum(),
+    ))
 }
 
+/// Usage: `day-09 [--input <path>] [--knots <n>] [--start <x>,<y>] [--at <step>] [--play]
+/// [--delay <ms>] [--gif <path>]`. Prints how many positions every knot visited and, if requested,
+/// where the knots were after the given step. `--play` animates the rope in the terminal and
+/// `--gif` writes the animation to a file, each step being shown for `--delay` milliseconds.
 fn main() {
-    let mut f = File::open("input.txt").unwrap();
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map(|index| match args.get(index + 1) {
+                Some(value) => value.as_str(),
+                None => panic!("{name} requires a value"),
+            })
+    };
+
+    let mut f = File::open(arg_value("--input").unwrap_or("input.txt")).unwrap();
     let mut input = String::new();
     f.read_to_string(&mut input).unwrap();
 
+    let knot_count = arg_value("--knots").map_or(10, |value| value.parse().unwrap());
+    let start = arg_value("--start").map_or((0, 0), |value| {
+        let (x, y) = value.split_once(',').unwrap();
+        (x.parse().unwrap(), y.parse().unwrap())
+    });
+    let at_step: Option<usize> = arg_value("--at").map(|value| value.parse().unwrap());
+    let play = args.iter().any(|arg| arg == "--play");
+    let gif_path = arg_value("--gif").map(Path::new);
+    let delay =
+        Duration::from_millis(arg_value("--delay").map_or(100, |value| value.parse().unwrap()));
+
+    // A knot only depends on the knots in front of it, so the second knot of the long rope moves
+    // exactly like the tail of the short rope of part one.
     let head_movements = parse_input(&input);
-    println!(
-        "Part One: {}",
-        count_unique_tail_positions(&head_movements, [(0, 0)].repeat(2))
-    );
-    println!(
-        "Part Two: {}",
-        count_unique_tail_positions(&head_movements, [(0, 0)].repeat(10))
-    );
+    let mut rope = if at_step.is_some() || play || gif_path.is_some() {
+        Rope::with_history(knot_count, start)
+    } else {
+        Rope::new(knot_count, start)
+    };
+    rope.simulate(&head_movements);
+    let visited_counts = rope.visited_counts();
+
+    if knot_count == 10 {
+        println!("Part One: {}", visited_counts[1]);
+        println!("Part Two: {}", visited_counts[9]);
+    }
+    for (knot, count) in visited_counts.iter().enumerate() {
+        println!("Knot {knot} visited {count} positions");
+    }
+    if let Some(step) = at_step {
+        println!("Positions after step {step}: {:?}", rope.positions_at(step));
+    }
+
+    if play {
+        render::play(render::Animation::new(&rope).unwrap(), delay);
+    }
+    if let Some(path) = gif_path {
+        let animation = render::Animation::new(&rope).unwrap();
+        if let Err(err) = render::write_gif(path, animation, 4, delay) {
+            eprintln!("Can't write the GIF: {err:?}");
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::*;
 
+    fn count_unique_tail_positions(head_movements: &[Motion], knot_count: usize) -> usize {
+        let mut rope = Rope::new(knot_count, (0, 0));
+        rope.simulate(head_movements);
+        rope.visited_counts()[knot_count - 1]
+    }
+
     const EXAMPLE_INPUT: &str = "R 4
 U 4
 L 3
@@ -141,21 +176,21 @@ U 20";
     #[test]
     fn part1_works() {
         let head_movements = parse_input(EXAMPLE_INPUT);
-        let result = count_unique_tail_positions(&head_movements, [(0, 0)].repeat(2));
+        let result = count_unique_tail_positions(&head_movements, 2);
         assert_eq!(result, 13);
     }
 
     #[test]
     fn part2_works_simple() {
         let head_movements = parse_input(EXAMPLE_INPUT);
-        let result_simple = count_unique_tail_positions(&head_movements, [(0, 0)].repeat(10));
+        let result_simple = count_unique_tail_positions(&head_movements, 10);
         assert_eq!(result_simple, 1);
     }
 
     #[test]
     fn part2_works_larger() {
         let head_movements = parse_input(EXAMPLE_LARGER_INPUT);
-        let result_larger = count_unique_tail_positions(&head_movements, [(0, 0)].repeat(10));
+        let result_larger = count_unique_tail_positions(&head_movements, 10);
         assert_eq!(result_larger, 36);
     }
 
@@ -170,6 +205,8 @@ U 20";
         assert!(is_neighboring_point(&(2, 2), &(3, 3)));
 
         assert!(!is_neighboring_point(&(1, 2), &(3, 3)));
+        assert!(!is_neighboring_point(&(2, 2), &(4, 4)));
+        assert!(!is_neighboring_point(&(2, 2), &(0, 3)));
     }
 
     #[test]
@@ -179,5 +216,39 @@ U 20";
         assert_eq!(move_tail(&(2, 2), &(2, 0)), Some((2, 1)));
         assert_eq!(move_tail(&(2, 2), &(0, 2)), Some((1, 2)));
         assert_eq!(move_tail(&(2, 2), &(0, 1)), Some((1, 2)));
+        assert_eq!(move_tail(&(2, 2), &(0, 0)), Some((1, 1)));
+    }
+
+    #[test]
+    fn parse_input_works() {
+        let motions = parse_input("R 1000000\nUL 3\nDR 2");
+        assert_eq!(
+            motions,
+            vec![
+                Motion {
+                    movement: HeadMovement::Right,
+                    count: 1000000
+                },
+                Motion {
+                    movement: HeadMovement::UpLeft,
+                    count: 3
+                },
+                Motion {
+                    movement: HeadMovement::DownRight,
+                    count: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diagonal_movements_work() {
+        let head_movements = parse_input("UR 3\nDL 1");
+        let result = count_unique_tail_positions(&head_movements, 2);
+        assert_eq!(result, 3);
+
+        let head_movements = parse_input("R 10000");
+        let result = count_unique_tail_positions(&head_movements, 10);
+        assert_eq!(result, 10000 - 8);
     }
 }
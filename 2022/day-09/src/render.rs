@@ -0,0 +1,367 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use crate::{rope::Rope, Position};
+
+type Frame = Vec<Vec<char>>;
+
+/// The area covered by all rendered frames, so every frame has the same size.
+#[derive(Debug, PartialEq)]
+pub struct Bounds {
+    min: Position,
+    max: Position,
+}
+
+impl Bounds {
+    fn of<'a>(positions: impl Iterator<Item = &'a Position>) -> Bounds {
+        positions.fold(
+            Bounds {
+                min: (i64::MAX, i64::MAX),
+                max: (i64::MIN, i64::MIN),
+            },
+            |bounds, pos| Bounds {
+                min: (bounds.min.0.min(pos.0), bounds.min.1.min(pos.1)),
+                max: (bounds.max.0.max(pos.0), bounds.max.1.max(pos.1)),
+            },
+        )
+    }
+
+    fn width(&self) -> usize {
+        (self.max.0 - self.min.0 + 1) as usize
+    }
+
+    fn height(&self) -> usize {
+        (self.max.1 - self.min.1 + 1) as usize
+    }
+}
+
+/// The label the puzzle uses for a knot: `H` for the head, `T` for the tail of a two knot rope
+/// and the knot's number otherwise. Knots past the ninth, which the puzzle never has, are all drawn
+/// as `*` so their labels can't be mistaken for the start or another marker.
+fn knot_label(knot: usize, knot_count: usize) -> char {
+    match knot {
+        0 => 'H',
+        1 if knot_count == 2 => 'T',
+        1..=9 => char::from_digit(knot as u32, 10).unwrap(),
+        _ => '*',
+    }
+}
+
+/// A rectangle of cells as top left and bottom right corner, each as (column, row).
+type Area = ((usize, usize), (usize, usize));
+
+/// The rope's steps drawn one after another into a single frame: the knots, the start `s` and the
+/// positions visited by the tail `#`. Where knots overlap the one closer to the head is shown, just
+/// like in the puzzle description. The y axis points up.
+///
+/// Only the cells a step's knots leave and enter are redrawn, so a step costs time proportional to
+/// the number of knots rather than to the size of the frame.
+pub struct Animation<'a> {
+    rope: &'a Rope,
+    bounds: Bounds,
+    start: Position,
+    tail_visited: HashSet<Position>,
+    frame: Frame,
+    step: usize,
+}
+
+impl<'a> Animation<'a> {
+    /// The animation at the initial state, or `None` if the rope wasn't created
+    /// [`Rope::with_history`].
+    pub fn new(rope: &'a Rope) -> Option<Animation<'a>> {
+        let knots = rope.positions_at(0)?;
+        let bounds = Bounds::of((0..).map_while(|step| rope.positions_at(step)).flatten());
+        let frame = vec![vec!['.'; bounds.width()]; bounds.height()];
+
+        let mut animation = Animation {
+            rope,
+            bounds,
+            start: knots[0],
+            tail_visited: HashSet::from([*knots.last().unwrap()]),
+            frame,
+            step: 0,
+        };
+        animation.draw_knots(knots);
+        Some(animation)
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Moves on to the next step, returning the area of the frame that changed, or `None` after
+    /// the last step.
+    pub fn advance(&mut self) -> Option<Area> {
+        let rope = self.rope;
+        let previous = rope.positions_at(self.step)?;
+        let knots = rope.positions_at(self.step + 1)?;
+        self.step += 1;
+        self.tail_visited.insert(*knots.last().unwrap());
+
+        let mut area: Option<Area> = None;
+        for pos in previous.iter().chain(knots) {
+            let (col, row) = self.cell(pos);
+            self.frame[row][col] = self.background(pos);
+            area = Some(match area {
+                None => ((col, row), (col, row)),
+                Some((min, max)) => (
+                    (min.0.min(col), min.1.min(row)),
+                    (max.0.max(col), max.1.max(row)),
+                ),
+            });
+        }
+        self.draw_knots(knots);
+
+        area
+    }
+
+    fn cell(&self, pos: &Position) -> (usize, usize) {
+        (
+            (pos.0 - self.bounds.min.0) as usize,
+            (self.bounds.max.1 - pos.1) as usize,
+        )
+    }
+
+    /// What a cell shows without any knot on it.
+    fn background(&self, pos: &Position) -> char {
+        if *pos == self.start {
+            's'
+        } else if self.tail_visited.contains(pos) {
+            '#'
+        } else {
+            '.'
+        }
+    }
+
+    fn draw_knots(&mut self, knots: &[Position]) {
+        for (knot, pos) in knots.iter().enumerate().rev() {
+            let (col, row) = self.cell(pos);
+            self.frame[row][col] = knot_label(knot, knots.len());
+        }
+    }
+}
+
+pub fn frame_to_string(frame: &Frame) -> String {
+    frame
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Plays the animation in the terminal, clearing the screen before each step.
+pub fn play(mut animation: Animation, delay: Duration) {
+    let mut stdout = std::io::stdout().lock();
+    loop {
+        write!(
+            stdout,
+            "\x1b[2J\x1b[H{}\nStep {}\n",
+            frame_to_string(animation.frame()),
+            animation.step()
+        )
+        .unwrap();
+        stdout.flush().unwrap();
+        thread::sleep(delay);
+
+        if animation.advance().is_none() {
+            break;
+        }
+    }
+}
+
+const GIF_PALETTE: &[u8] = &[
+    24, 24, 32, // .
+    96, 96, 112, // #
+    64, 128, 255, // s
+    255, 64, 64, // H
+    255, 176, 48, // other knots
+];
+
+fn gif_colour_index(c: char) -> u8 {
+    match c {
+        '.' => 0,
+        '#' => 1,
+        's' => 2,
+        'H' => 3,
+        _ => 4,
+    }
+}
+
+/// The frame has more cells in a direction than a GIF has pixels, even at one pixel per cell.
+#[derive(Debug, PartialEq)]
+pub struct FrameTooLarge {
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Writes the animation as animated GIF, each step being shown for `delay`. Each cell takes
+/// `max_scale`×`max_scale` pixels, or less where the image would exceed the 65535 pixels a GIF can
+/// be wide or high. After the first frame only the area changed by a step is written.
+pub fn write_gif(
+    path: &Path,
+    mut animation: Animation,
+    max_scale: usize,
+    delay: Duration,
+) -> Result<(), FrameTooLarge> {
+    let (cells_width, cells_height) = (animation.bounds.width(), animation.bounds.height());
+    let max_pixels = u16::MAX as usize;
+    let scale = max_scale
+        .min(max_pixels / cells_width)
+        .min(max_pixels / cells_height);
+    if scale == 0 {
+        return Err(FrameTooLarge {
+            width: cells_width,
+            height: cells_height,
+        });
+    }
+
+    let file = BufWriter::new(File::create(path).unwrap());
+    let (width, height) = (cells_width * scale, cells_height * scale);
+    let mut encoder = gif::Encoder::new(file, width as u16, height as u16, GIF_PALETTE).unwrap();
+    encoder.set_repeat(gif::Repeat::Infinite).unwrap();
+
+    let mut write_area = |frame: &Frame, (min, max): Area| {
+        let (area_width, area_height) = ((max.0 - min.0 + 1) * scale, (max.1 - min.1 + 1) * scale);
+        let mut pixels = Vec::with_capacity(area_width * area_height);
+        for row in &frame[min.1..=max.1] {
+            let pixel_row: Vec<_> = row[min.0..=max.0]
+                .iter()
+                .flat_map(|c| [gif_colour_index(*c)].repeat(scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&pixel_row);
+            }
+        }
+
+        let mut gif_frame =
+            gif::Frame::from_indexed_pixels(area_width as u16, area_height as u16, pixels, None);
+        gif_frame.left = (min.0 * scale) as u16;
+        gif_frame.top = (min.1 * scale) as u16;
+        gif_frame.dispose = gif::DisposalMethod::Keep;
+        gif_frame.delay = (delay.as_millis() / 10) as u16;
+        encoder.write_frame(&gif_frame).unwrap();
+    };
+
+    write_area(
+        animation.frame(),
+        ((0, 0), (cells_width - 1, cells_height - 1)),
+    );
+    while let Some(area) = animation.advance() {
+        write_area(animation.frame(), area);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    /// Every frame of the animation, from the initial state to the last step.
+    fn render_frames(rope: &Rope) -> Vec<Frame> {
+        let mut animation = Animation::new(rope).unwrap();
+        let mut frames = vec![animation.frame().clone()];
+        while animation.advance().is_some() {
+            frames.push(animation.frame().clone());
+        }
+        frames
+    }
+
+    #[test]
+    fn render_frames_works() {
+        let mut rope = Rope::with_history(2, (0, 0));
+        rope.simulate(&parse_input("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2"));
+
+        let frames = render_frames(&rope);
+        assert_eq!(frames.len(), 25);
+        assert_eq!(
+            frame_to_string(&frames[0]),
+            "......\n......\n......\n......\nH....."
+        );
+        assert_eq!(
+            frame_to_string(&frames[4]),
+            "......\n......\n......\n......\ns##TH."
+        );
+        assert_eq!(
+            frame_to_string(&frames[24]),
+            "..##..\n...##.\n.TH##.\n....#.\ns###.."
+        );
+    }
+
+    #[test]
+    fn render_frames_ten_knots_works() {
+        let mut rope = Rope::with_history(10, (0, 0));
+        rope.simulate(&parse_input("R 4\nU 2"));
+
+        let frames = render_frames(&rope);
+        assert_eq!(frame_to_string(&frames[6]), "....H\n.4321\n5....");
+    }
+
+    #[test]
+    fn knot_labels_work() {
+        assert_eq!(knot_label(0, 2), 'H');
+        assert_eq!(knot_label(1, 2), 'T');
+        assert_eq!(knot_label(1, 10), '1');
+        assert_eq!(knot_label(9, 10), '9');
+        // Base 36 would have drawn the 28th knot as the start marker
+        assert_eq!(knot_label(28, 30), '*');
+    }
+
+    #[test]
+    fn advance_works() {
+        let mut rope = Rope::with_history(2, (0, 0));
+        rope.simulate(&parse_input("R 2\nU 1"));
+
+        let mut animation = Animation::new(&rope).unwrap();
+        assert_eq!(frame_to_string(animation.frame()), "...\nH..");
+        // The head leaves the start for the cell to its right
+        assert_eq!(animation.advance(), Some(((0, 1), (1, 1))));
+        assert_eq!(animation.advance(), Some(((0, 1), (2, 1))));
+        assert_eq!(animation.advance(), Some(((1, 0), (2, 1))));
+        assert_eq!(frame_to_string(animation.frame()), "..H\nsT.");
+        assert_eq!(animation.step(), 3);
+        assert_eq!(animation.advance(), None);
+
+        assert!(Animation::new(&Rope::new(2, (0, 0))).is_none());
+    }
+
+    #[test]
+    fn write_gif_rejects_huge_frames() {
+        let mut rope = Rope::with_history(2, (0, 0));
+        rope.simulate(&parse_input("R 70000"));
+
+        let path = std::env::temp_dir().join("day-09-too-large.gif");
+        assert_eq!(
+            write_gif(&path, Animation::new(&rope).unwrap(), 4, Duration::ZERO),
+            Err(FrameTooLarge {
+                width: 70001,
+                height: 1
+            })
+        );
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn bounds_work() {
+        let bounds = Bounds::of([(1, -2), (-3, 4)].iter());
+        assert_eq!(
+            bounds,
+            Bounds {
+                min: (-3, -2),
+                max: (1, 4)
+            }
+        );
+        assert_eq!(bounds.width(), 5);
+        assert_eq!(bounds.height(), 7);
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+use crate::{move_head, move_tail, HeadMovement, Motion, Position};
+
+/// A rope made of any number of knots, the first one being the head. Every knot's visited
+/// positions are recorded, and optionally the positions of all knots after every step.
+pub struct Rope {
+    knots: Vec<Position>,
+    visited: Vec<HashSet<Position>>,
+    history: Option<Vec<Position>>,
+}
+
+impl Rope {
+    pub fn new(knot_count: usize, start: Position) -> Rope {
+        assert!(knot_count > 0, "A rope needs at least one knot");
+
+        Rope {
+            knots: vec![start; knot_count],
+            visited: vec![HashSet::from([start]); knot_count],
+            history: None,
+        }
+    }
+
+    /// Like [`Rope::new`], but also records the positions of all knots after every step, which
+    /// takes memory proportional to steps × knots.
+    pub fn with_history(knot_count: usize, start: Position) -> Rope {
+        let mut rope = Rope::new(knot_count, start);
+        rope.history = Some(rope.knots.clone());
+        rope
+    }
+
+    /// Moves the head by a single step and lets the other knots follow.
+    pub fn step(&mut self, movement: &HeadMovement) {
+        move_head(&mut self.knots[0], movement);
+        self.visited[0].insert(self.knots[0]);
+
+        for tail_index in 1..self.knots.len() {
+            match move_tail(&self.knots[tail_index - 1], &self.knots[tail_index]) {
+                Some(moved_tail_pos) => {
+                    self.knots[tail_index] = moved_tail_pos;
+                    self.visited[tail_index].insert(moved_tail_pos);
+                }
+                // If this knot doesn't move, none of the following will either
+                None => break,
+            }
+        }
+
+        if let Some(history) = &mut self.history {
+            history.extend_from_slice(&self.knots);
+        }
+    }
+
+    pub fn simulate(&mut self, motions: &[Motion]) {
+        for motion in motions {
+            for _ in 0..motion.count {
+                self.step(&motion.movement);
+            }
+        }
+    }
+
+    /// The positions of all knots after `step` steps, step 0 being the start. Only available if
+    /// the rope was created [`Rope::with_history`].
+    pub fn positions_at(&self, step: usize) -> Option<&[Position]> {
+        let knot_count = self.knots.len();
+        self.history
+            .as_ref()?
+            .get(step * knot_count..(step + 1) * knot_count)
+    }
+
+    /// How many positions each knot has visited, indexed by knot.
+    pub fn visited_counts(&self) -> Vec<usize> {
+        self.visited
+            .iter()
+            .map(|positions| positions.len())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    #[test]
+    fn visited_counts_work() {
+        let head_movements = parse_input("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2");
+        let mut rope = Rope::new(10, (0, 0));
+        rope.simulate(&head_movements);
+
+        let counts = rope.visited_counts();
+        assert_eq!(counts.len(), 10);
+        assert_eq!(counts[1], 13);
+        assert_eq!(counts[9], 1);
+        assert!(rope.visited[0].contains(&(4, 4)));
+    }
+
+    #[test]
+    fn positions_at_works() {
+        let mut rope = Rope::with_history(3, (5, 5));
+        rope.simulate(&parse_input("R 2\nU 1"));
+
+        assert_eq!(rope.positions_at(0), Some(&[(5, 5), (5, 5), (5, 5)][..]));
+        assert_eq!(rope.positions_at(2), Some(&[(7, 5), (6, 5), (5, 5)][..]));
+        assert_eq!(rope.positions_at(3), Some(&[(7, 6), (6, 5), (5, 5)][..]));
+        assert_eq!(rope.positions_at(4), None);
+        assert_eq!(rope.knots, vec![(7, 6), (6, 5), (5, 5)]);
+
+        let mut rope = Rope::new(3, (0, 0));
+        rope.step(&HeadMovement::Right);
+        assert_eq!(rope.positions_at(0), None);
+    }
+}
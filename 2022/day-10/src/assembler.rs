@@ -0,0 +1,242 @@
+use crate::{render_crt_image, sprite_range, CrtConfig, Instruction};
+
+/// Lit pixels of an image, row by row.
+pub type Bitmap = Vec<Vec<bool>>;
+
+/// The letters of the font the CRT uses in the puzzle, each 4 pixels wide and 6 pixels high.
+const FONT: [(char, [&str; 6]); 16] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads an image like the ones [`render_crt_image`] produces, `lit` marking the lit pixels.
+pub fn parse_bitmap(image: &str, lit: char) -> Bitmap {
+    image
+        .lines()
+        .map(|line| line.chars().map(|c| c == lit).collect())
+        .collect()
+}
+
+/// Renders the text in the puzzle's font, each letter followed by an empty column, so eight
+/// letters fill the 40×6 screen. Returns the first character the font doesn't know as error.
+pub fn text_to_bitmap(text: &str) -> Result<Bitmap, char> {
+    let mut bitmap: Bitmap = vec![Vec::new(); 6];
+
+    for c in text.chars() {
+        let (_, glyph) = FONT
+            .iter()
+            .find(|(letter, _)| *letter == c.to_ascii_uppercase())
+            .ok_or(c)?;
+
+        for (row, glyph_row) in bitmap.iter_mut().zip(glyph) {
+            row.extend(glyph_row.chars().map(|pixel| pixel == '#'));
+            row.push(false);
+        }
+    }
+
+    Ok(bitmap)
+}
+
+pub struct Assembly {
+    pub program: Vec<Instruction>,
+    /// Pixels as `(row, column)` which the program draws differently than requested, because no
+    /// program can draw them together with the rest of the image.
+    pub unreachable: Vec<(usize, usize)>,
+}
+
+/// Values the register may take that make a difference: the sprite may cover any column of the
+/// screen, or none at all at the lowest and highest value.
+fn register_values(config: &CrtConfig) -> Vec<i32> {
+    let reach = config.sprite_width as i32 / 2 + 1;
+    (-reach..config.width as i32 + reach).collect()
+}
+
+/// Finds the program drawing `target` with as few wrong pixels as possible.
+///
+/// The register starts at 1 and can only change after an `addx`, which takes two cycles, so every
+/// register value has to be kept for at least two cycles before the next one. A dynamic program
+/// over the cycles tracks, for every register value and for whether it has been kept long enough
+/// to be changed, the fewest wrong pixels so far. Walking back from the best final state yields
+/// the register value of every cycle, which is then turned into `noop`s and `addx`s.
+pub fn assemble(target: &Bitmap, config: &CrtConfig) -> Assembly {
+    let values = register_values(config);
+    let cycle_count = config.width * config.height;
+
+    let wrong_pixel = |cycle: usize, value: i32| -> usize {
+        let (row, column) = (cycle / config.width, cycle % config.width);
+        let wanted = target
+            .get(row)
+            .and_then(|pixels| pixels.get(column))
+            .copied()
+            .unwrap_or(false);
+        let lit = sprite_range(value, config.sprite_width).contains(&(column as i32));
+        (wanted != lit) as usize
+    };
+
+    // costs[cycle][value index][changeable], with the state it was reached from for walking back
+    const UNREACHED: usize = usize::MAX;
+    let mut costs = vec![vec![[UNREACHED; 2]; values.len()]; cycle_count];
+    let mut previous = vec![vec![[(0, 0); 2]; values.len()]; cycle_count];
+
+    let start_index = values.iter().position(|value| *value == 1).unwrap();
+    costs[0][start_index][0] = wrong_pixel(0, 1);
+
+    for cycle in 1..cycle_count {
+        let best_changeable = (0..values.len())
+            .filter(|index| costs[cycle - 1][*index][1] != UNREACHED)
+            .min_by_key(|index| costs[cycle - 1][*index][1]);
+
+        for (index, value) in values.iter().enumerate() {
+            let wrong = wrong_pixel(cycle, *value);
+
+            // Keep the value, which makes it changeable from the next cycle on
+            for changeable in 0..2 {
+                let cost = costs[cycle - 1][index][changeable];
+                if cost != UNREACHED && cost + wrong < costs[cycle][index][1] {
+                    costs[cycle][index][1] = cost + wrong;
+                    previous[cycle][index][1] = (index, changeable);
+                }
+            }
+
+            // Change to this value from the cheapest changeable one
+            if let Some(from) = best_changeable.filter(|from| *from != index) {
+                let cost = costs[cycle - 1][from][1] + wrong;
+                if cost < costs[cycle][index][0] {
+                    costs[cycle][index][0] = cost;
+                    previous[cycle][index][0] = (from, 1);
+                }
+            }
+        }
+    }
+
+    let mut state = (0..values.len())
+        .flat_map(|index| [(index, 0), (index, 1)])
+        .filter(|(index, changeable)| costs[cycle_count - 1][*index][*changeable] != UNREACHED)
+        .min_by_key(|(index, changeable)| costs[cycle_count - 1][*index][*changeable])
+        .unwrap();
+
+    let mut value_by_cycle = vec![0; cycle_count];
+    for cycle in (0..cycle_count).rev() {
+        value_by_cycle[cycle] = values[state.0];
+        state = previous[cycle][state.0][state.1];
+    }
+
+    let program = values_to_program(&value_by_cycle);
+    let unreachable = verify(&program, target, config);
+    Assembly {
+        program,
+        unreachable,
+    }
+}
+
+/// Every run of a register value ends with the `addx` changing it to the next one.
+fn values_to_program(value_by_cycle: &[i32]) -> Vec<Instruction> {
+    let mut program = Vec::new();
+
+    let mut run_start = 0;
+    for cycle in 1..=value_by_cycle.len() {
+        let next_value = value_by_cycle.get(cycle);
+        if next_value == Some(&value_by_cycle[run_start]) {
+            continue;
+        }
+
+        let run_length = cycle - run_start;
+        match next_value {
+            Some(next_value) => {
+                program.extend((0..run_length - 2).map(|_| Instruction::Noop));
+                program.push(Instruction::AddX(next_value - value_by_cycle[run_start]));
+            }
+            None => program.extend((0..run_length).map(|_| Instruction::Noop)),
+        }
+        run_start = cycle;
+    }
+
+    program
+}
+
+/// Runs the program and returns the pixels that differ from the target.
+fn verify(program: &[Instruction], target: &Bitmap, config: &CrtConfig) -> Vec<(usize, usize)> {
+    let rendered = parse_bitmap(&render_crt_image(program, config), config.lit);
+
+    (0..config.height)
+        .flat_map(|row| (0..config.width).map(move |column| (row, column)))
+        .filter(|(row, column)| {
+            let wanted = target.get(*row).and_then(|pixels| pixels.get(*column));
+            rendered[*row][*column] != wanted.copied().unwrap_or(false)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, simulate_register_value_by_cycle};
+
+    #[test]
+    fn text_to_bitmap_works() {
+        let bitmap = text_to_bitmap("ZRARLFZU").unwrap();
+        assert_eq!(bitmap.len(), 6);
+        assert_eq!(bitmap[0].len(), 40);
+        assert_eq!(
+            bitmap[3]
+                .iter()
+                .map(|lit| if *lit { '#' } else { '.' })
+                .collect::<String>(),
+            ".#...###..####.###..#....#.....#...#..#."
+        );
+
+        assert_eq!(text_to_bitmap("ZQ"), Err('Q'));
+    }
+
+    #[test]
+    fn assemble_text_works() {
+        let config = CrtConfig::default();
+        let target = text_to_bitmap("ECZUZRFA").unwrap();
+
+        let assembly = assemble(&target, &config);
+        assert_eq!(assembly.unreachable, vec![]);
+        assert_eq!(
+            parse_bitmap(&render_crt_image(&assembly.program, &config), '#'),
+            target
+        );
+
+        let value_by_cycle = simulate_register_value_by_cycle(&assembly.program);
+        assert_eq!(value_by_cycle.len(), 241);
+    }
+
+    #[test]
+    fn assemble_reports_unreachable_pixels() {
+        let config = CrtConfig::default();
+
+        // The register starts at 1 and is kept for at least two cycles, so the first two pixels
+        // are always lit, but an `H` starts with a lit pixel followed by a dark one.
+        let target = text_to_bitmap("HHHHHHHH").unwrap();
+
+        let assembly = assemble(&target, &config);
+        assert_eq!(assembly.unreachable, vec![(0, 1)]);
+    }
+
+    #[test]
+    fn values_to_program_works() {
+        let program = values_to_program(&[1, 1, 1, 5, 5, 3, 3, 3]);
+        let value_by_cycle = simulate_register_value_by_cycle(&program);
+        assert_eq!(value_by_cycle[..8], [1, 1, 1, 5, 5, 3, 3, 3]);
+
+        let program = parse_input("noop\naddx 4\naddx -2\nnoop\nnoop\nnoop");
+        assert_eq!(values_to_program(&[1, 1, 1, 5, 5, 3, 3, 3]), program);
+    }
+}
@@ -1,12 +1,77 @@
-use std::{fs::File, io::Read, ops::Range};
+use std::{fmt, fs::File, io::Read, ops::Range};
 
+mod assembler;
+
+#[derive(Debug, PartialEq)]
 enum Instruction {
     Noop,
     AddX(i32),
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::AddX(value) => write!(f, "addx {}", value),
+        }
+    }
+}
+
+/// Dimensions of the screen, width of the sprite and the characters used to draw pixels.
+struct CrtConfig {
+    width: usize,
+    height: usize,
+    sprite_width: usize,
+    lit: char,
+    dark: char,
+}
+
+impl Default for CrtConfig {
+    fn default() -> Self {
+        CrtConfig {
+            width: 40,
+            height: 6,
+            sprite_width: 3,
+            lit: '#',
+            dark: '.',
+        }
+    }
+}
+
+/// The cycles during which the signal strength is sampled, 1-based.
+enum SamplingSchedule {
+    /// `count` cycles, the first being `start` and each following one `step` cycles later.
+    Stepped {
+        start: usize,
+        step: usize,
+        count: usize,
+    },
+    Cycles(Vec<usize>),
+}
+
+impl Default for SamplingSchedule {
+    fn default() -> Self {
+        SamplingSchedule::Stepped {
+            start: 20,
+            step: 40,
+            count: 6,
+        }
+    }
+}
+
+impl SamplingSchedule {
+    fn cycles(&self) -> Vec<usize> {
+        match self {
+            SamplingSchedule::Stepped { start, step, count } => {
+                (0..*count).map(|i| start + i * step).collect()
+            }
+            SamplingSchedule::Cycles(cycles) => cycles.clone(),
+        }
+    }
+}
+
 fn parse_instruction(input: &str) -> Instruction {
-    let parts: Vec<_> = input.trim().split_whitespace().collect();
+    let parts: Vec<_> = input.split_whitespace().collect();
 
     match parts[0] {
         "noop" => Instruction::Noop,
@@ -16,14 +81,10 @@ fn parse_instruction(input: &str) -> Instruction {
 }
 
 fn parse_input(input: &str) -> Vec<Instruction> {
-    input
-        .trim()
-        .split("\n")
-        .map(|line| parse_instruction(line))
-        .collect()
+    input.trim().lines().map(parse_instruction).collect()
 }
 
-fn simulate_register_value_by_cycle(program: &Vec<Instruction>) -> Vec<i32> {
+fn simulate_register_value_by_cycle(program: &[Instruction]) -> Vec<i32> {
     let mut value_by_cycle = vec![1];
 
     for instruction in program {
@@ -37,53 +98,138 @@ fn simulate_register_value_by_cycle(program: &Vec<Instruction>) -> Vec<i32> {
     value_by_cycle
 }
 
-fn sum_signal_strengths(program: &Vec<Instruction>) -> i32 {
+/// The register keeps its last value once the program has finished. Cycles are 1-based, so a
+/// cycle 0 has a signal strength of 0.
+fn sum_signal_strengths(program: &[Instruction], schedule: &SamplingSchedule) -> i32 {
     let value_by_cycle = simulate_register_value_by_cycle(program);
-    [20, 60, 100, 140, 180, 220]
+    let last_value = *value_by_cycle.last().unwrap();
+
+    schedule
+        .cycles()
         .iter()
-        .map(|cycle| cycle * value_by_cycle[(*cycle - 1) as usize])
+        .map(|cycle| {
+            let x_value = cycle
+                .checked_sub(1)
+                .and_then(|index| value_by_cycle.get(index))
+                .copied()
+                .unwrap_or(last_value);
+            *cycle as i32 * x_value
+        })
         .sum()
 }
 
-fn sprite_range(x_value: i32) -> Range<i32> {
-    x_value - 1..x_value + 2
+/// The pixels covered by a sprite of `sprite_width` centered at `x_value`.
+fn sprite_range(x_value: i32, sprite_width: usize) -> Range<i32> {
+    let left = x_value - (sprite_width as i32 - 1) / 2;
+    left..left + sprite_width as i32
 }
 
-fn render_crt_image(program: &Vec<Instruction>) -> String {
-    const SCREEN_WIDTH: usize = 40;
-    const SCREEN_HEIGHT: usize = 6;
-
+fn render_crt_image(program: &[Instruction], config: &CrtConfig) -> String {
     let value_by_cycle = simulate_register_value_by_cycle(program);
     let complete_string: Vec<char> = value_by_cycle
         .iter()
-        .take(SCREEN_WIDTH * SCREEN_HEIGHT)
+        .take(config.width * config.height)
         .enumerate()
         .map(|(cycle, x_value)| {
-            if sprite_range(*x_value).contains(&((cycle % SCREEN_WIDTH) as i32)) {
-                '#'
+            let column = (cycle % config.width) as i32;
+            if sprite_range(*x_value, config.sprite_width).contains(&column) {
+                config.lit
             } else {
-                '.'
+                config.dark
             }
         })
         .collect();
 
-    let lines: Vec<&[char]> = complete_string.chunks(SCREEN_WIDTH).collect();
-    lines
-        .iter()
-        .copied()
+    complete_string
+        .chunks(config.width)
         .map(|line| line.iter().collect::<String>())
         .collect::<Vec<String>>()
         .join("\n")
 }
 
+/// Usage: `day-10 [--input <path>] [--width <n>] [--height <n>] [--sprite-width <n>] [--lit <c>]
+/// [--dark <c>] [--sample-start <n>] [--sample-step <n>] [--sample-count <n>]
+/// [--samples <n>,<n>,...]`, everything not given defaults to the puzzle's values. The width and
+/// height have to be at least 1.
+///
+/// With `--assemble-text <text>` or `--assemble <image path>` a program drawing the text or image
+/// is printed instead, and pixels that can't be drawn are reported.
 fn main() {
-    let mut f = File::open("input.txt").unwrap();
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map(|index| match args.get(index + 1) {
+                Some(value) => value.as_str(),
+                None => panic!("{name} requires a value"),
+            })
+    };
+    let number_arg = |name: &str, default: usize| {
+        arg_value(name).map_or(default, |value| value.parse().unwrap())
+    };
+    let positive_arg = |name: &str, default: usize| {
+        let value = number_arg(name, default);
+        assert!(value > 0, "{name} must be at least 1");
+        value
+    };
+    let char_arg = |name: &str, default: char| {
+        arg_value(name).map_or(default, |value| value.chars().next().unwrap())
+    };
+
+    let defaults = CrtConfig::default();
+    let config = CrtConfig {
+        width: positive_arg("--width", defaults.width),
+        height: positive_arg("--height", defaults.height),
+        sprite_width: number_arg("--sprite-width", defaults.sprite_width),
+        lit: char_arg("--lit", defaults.lit),
+        dark: char_arg("--dark", defaults.dark),
+    };
+
+    let schedule = match (arg_value("--samples"), SamplingSchedule::default()) {
+        (Some(cycles), _) => SamplingSchedule::Cycles(
+            cycles
+                .split(',')
+                .map(|cycle| cycle.parse().unwrap())
+                .collect(),
+        ),
+        (None, SamplingSchedule::Stepped { start, step, count }) => SamplingSchedule::Stepped {
+            start: number_arg("--sample-start", start),
+            step: number_arg("--sample-step", step),
+            count: number_arg("--sample-count", count),
+        },
+        (None, schedule) => schedule,
+    };
+
+    let target = match (arg_value("--assemble-text"), arg_value("--assemble")) {
+        (Some(text), _) => Some(assembler::text_to_bitmap(text).unwrap()),
+        (None, Some(path)) => {
+            let mut image = String::new();
+            File::open(path)
+                .unwrap()
+                .read_to_string(&mut image)
+                .unwrap();
+            Some(assembler::parse_bitmap(&image, config.lit))
+        }
+        (None, None) => None,
+    };
+    if let Some(target) = target {
+        let assembly = assembler::assemble(&target, &config);
+        for instruction in &assembly.program {
+            println!("{}", instruction);
+        }
+        for (row, column) in assembly.unreachable {
+            eprintln!("Unreachable pixel at row {row}, column {column}");
+        }
+        return;
+    }
+
+    let mut f = File::open(arg_value("--input").unwrap_or("input.txt")).unwrap();
     let mut input = String::new();
     f.read_to_string(&mut input).unwrap();
 
     let program = parse_input(&input);
-    println!("Part One: {}", sum_signal_strengths(&program));
-    println!("Part Two:\n{}", render_crt_image(&program));
+    println!("Part One: {}", sum_signal_strengths(&program, &schedule));
+    println!("Part Two:\n{}", render_crt_image(&program, &config));
 }
 
 #[cfg(test)]
@@ -240,14 +386,14 @@ noop";
     #[test]
     fn part1_works() {
         let program = parse_input(EXAMPLE_INPUT);
-        let result = sum_signal_strengths(&program);
+        let result = sum_signal_strengths(&program, &SamplingSchedule::default());
         assert_eq!(result, 13140);
     }
 
     #[test]
     fn part2_works() {
         let program = parse_input(EXAMPLE_INPUT);
-        let result = render_crt_image(&program);
+        let result = render_crt_image(&program, &CrtConfig::default());
         assert_eq!(
             result,
             "##..##..##..##..##..##..##..##..##..##..
@@ -258,4 +404,50 @@ noop";
 #######.......#######.......#######....."
         );
     }
+
+    #[test]
+    fn sampling_schedule_works() {
+        let program = parse_input(EXAMPLE_INPUT);
+
+        let explicit = SamplingSchedule::Cycles(vec![20, 60, 100, 140, 180, 220]);
+        assert_eq!(sum_signal_strengths(&program, &explicit), 13140);
+
+        let schedule = SamplingSchedule::Stepped {
+            start: 20,
+            step: 40,
+            count: 2,
+        };
+        assert_eq!(schedule.cycles(), vec![20, 60]);
+        assert_eq!(sum_signal_strengths(&program, &schedule), 420 + 1140);
+
+        // Beyond the end of the program the register keeps its last value of 17
+        let beyond = SamplingSchedule::Cycles(vec![1000]);
+        assert_eq!(sum_signal_strengths(&program, &beyond), 17000);
+
+        let zero = SamplingSchedule::Cycles(vec![0, 20]);
+        assert_eq!(sum_signal_strengths(&program, &zero), 420);
+    }
+
+    #[test]
+    fn crt_config_works() {
+        let program = parse_input(&["noop"; 8].join("\n"));
+        let mut config = CrtConfig {
+            width: 4,
+            height: 2,
+            sprite_width: 1,
+            lit: '█',
+            dark: ' ',
+        };
+        assert_eq!(render_crt_image(&program, &config), " █  \n █  ");
+
+        config.sprite_width = 3;
+        assert_eq!(render_crt_image(&program, &config), "███ \n███ ");
+    }
+
+    #[test]
+    fn sprite_range_works() {
+        assert_eq!(sprite_range(5, 3), 4..7);
+        assert_eq!(sprite_range(5, 1), 5..6);
+        assert_eq!(sprite_range(5, 4), 4..8);
+    }
 }
@@ -0,0 +1,183 @@
+/// Which moves are allowed on the height map and what they cost. The default is the rule of the
+/// puzzle: climb at most one level, drop any amount, only horizontal and vertical moves, each
+/// costing one step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClimbRule {
+    /// How many levels a single move may climb.
+    pub max_ascent: u8,
+    /// How many levels a single move may drop.
+    pub max_descent: u8,
+    /// Whether diagonal neighbours can be reached in a single move.
+    pub diagonal: bool,
+    /// Cost of a move keeping the height.
+    pub flat_cost: usize,
+    /// Cost of a move going up.
+    pub ascent_cost: usize,
+    /// Cost of a move going down.
+    pub descent_cost: usize,
+    /// Added to the cost of a move for every level of height difference.
+    pub cost_per_level: usize,
+}
+
+/// A command line option of the rule without a value, or with one that isn't a number in the
+/// option's range.
+#[derive(Debug, PartialEq)]
+pub enum ClimbRuleError {
+    MissingValue { option: String },
+    InvalidValue { option: String, value: String },
+}
+
+impl Default for ClimbRule {
+    fn default() -> Self {
+        ClimbRule {
+            max_ascent: 1,
+            max_descent: u8::MAX,
+            diagonal: false,
+            flat_cost: 1,
+            ascent_cost: 1,
+            descent_cost: 1,
+            cost_per_level: 0,
+        }
+    }
+}
+
+impl ClimbRule {
+    /// The cost of moving from `from_height` to `to_height`, `None` if the move is not allowed.
+    pub fn cost(&self, from_height: u8, to_height: u8) -> Option<usize> {
+        let difference = from_height.abs_diff(to_height);
+        let base_cost = match from_height.cmp(&to_height) {
+            std::cmp::Ordering::Less if difference <= self.max_ascent => self.ascent_cost,
+            std::cmp::Ordering::Greater if difference <= self.max_descent => self.descent_cost,
+            std::cmp::Ordering::Equal => self.flat_cost,
+            _ => return None,
+        };
+
+        Some(base_cost + difference as usize * self.cost_per_level)
+    }
+
+    /// Whether every allowed move costs the same, which allows a breadth-first search.
+    pub fn is_uniform(&self) -> bool {
+        self.flat_cost == self.ascent_cost
+            && self.flat_cost == self.descent_cost
+            && self.cost_per_level == 0
+    }
+
+    /// The lowest cost any move can have.
+    pub fn min_cost(&self) -> usize {
+        self.flat_cost.min(self.ascent_cost).min(self.descent_cost)
+    }
+
+    /// Reads the rule from `--max-ascent <n>`, `--max-descent <n>`, `--diagonal`, `--flat-cost <n>`,
+    /// `--ascent-cost <n>`, `--descent-cost <n>` and `--cost-per-level <n>`, defaulting to the
+    /// puzzle rule for anything not given. Heights are bytes, so the ascent and descent are at most
+    /// 255.
+    pub fn from_args(args: &[String]) -> Result<ClimbRule, ClimbRuleError> {
+        fn value<T: std::str::FromStr>(
+            option: &str,
+            value: Option<&String>,
+        ) -> Result<T, ClimbRuleError> {
+            let value = value.ok_or_else(|| ClimbRuleError::MissingValue {
+                option: option.to_string(),
+            })?;
+            value.parse().map_err(|_| ClimbRuleError::InvalidValue {
+                option: option.to_string(),
+                value: value.clone(),
+            })
+        }
+
+        let mut rule = ClimbRule::default();
+
+        let mut args_iter = args.iter();
+        while let Some(arg) = args_iter.next() {
+            let arg = arg.as_str();
+            match arg {
+                "--max-ascent" => rule.max_ascent = value(arg, args_iter.next())?,
+                "--max-descent" => rule.max_descent = value(arg, args_iter.next())?,
+                "--diagonal" => rule.diagonal = true,
+                "--flat-cost" => rule.flat_cost = value(arg, args_iter.next())?,
+                "--ascent-cost" => rule.ascent_cost = value(arg, args_iter.next())?,
+                "--descent-cost" => rule.descent_cost = value(arg, args_iter.next())?,
+                "--cost-per-level" => rule.cost_per_level = value(arg, args_iter.next())?,
+                _ => {}
+            }
+        }
+
+        Ok(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_cost_works() {
+        let rule = ClimbRule::default();
+        assert_eq!(rule.cost(1, 1), Some(1));
+        assert_eq!(rule.cost(1, 2), Some(1));
+        assert_eq!(rule.cost(1, 3), None);
+        assert_eq!(rule.cost(26, 1), Some(1));
+        assert!(rule.is_uniform());
+    }
+
+    #[test]
+    fn weighted_cost_works() {
+        let rule = ClimbRule {
+            max_ascent: 2,
+            max_descent: 1,
+            descent_cost: 2,
+            cost_per_level: 3,
+            ..ClimbRule::default()
+        };
+        assert_eq!(rule.cost(1, 1), Some(1));
+        assert_eq!(rule.cost(1, 3), Some(7));
+        assert_eq!(rule.cost(2, 1), Some(5));
+        assert_eq!(rule.cost(3, 1), None);
+        assert!(!rule.is_uniform());
+        assert_eq!(rule.min_cost(), 1);
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn from_args_works() {
+        let args = args(&["--diagonal", "--descent-cost", "2", "--max-ascent", "3"]);
+
+        let rule = ClimbRule::from_args(&args);
+        assert_eq!(
+            rule,
+            Ok(ClimbRule {
+                max_ascent: 3,
+                diagonal: true,
+                descent_cost: 2,
+                ..ClimbRule::default()
+            })
+        );
+    }
+
+    #[test]
+    fn from_args_errors_are_reported() {
+        assert_eq!(
+            ClimbRule::from_args(&args(&["--max-ascent", "256"])),
+            Err(ClimbRuleError::InvalidValue {
+                option: "--max-ascent".to_string(),
+                value: "256".to_string()
+            })
+        );
+        assert_eq!(
+            ClimbRule::from_args(&args(&["--flat-cost", "x"])),
+            Err(ClimbRuleError::InvalidValue {
+                option: "--flat-cost".to_string(),
+                value: "x".to_string()
+            })
+        );
+        assert_eq!(
+            ClimbRule::from_args(&args(&["--diagonal", "--max-descent"])),
+            Err(ClimbRuleError::MissingValue {
+                option: "--max-descent".to_string()
+            })
+        );
+    }
+}
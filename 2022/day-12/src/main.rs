@@ -1,118 +1,242 @@
-use std::{fs::File, io::Read};
+use std::{collections::VecDeque, fs::File, io::Read};
 
 use pathfinding::prelude::*;
 
+mod climb_rule;
+mod render;
+
+use climb_rule::ClimbRule;
+
 type Position = (usize, usize);
 type HightMap = Vec<Vec<u8>>;
 
-fn input_char_to_height(c: char) -> u8 {
+#[derive(Debug, PartialEq)]
+enum ParseError {
+    /// There is no `S` on the map.
+    MissingStart,
+    /// There is no `E` on the map.
+    MissingGoal,
+    /// There is more than one `E` on the map, the positions of the first two are given.
+    DuplicateGoal(Position, Position),
+    /// A character which is neither `S`, `E` nor a height from `a` to `z`.
+    InvalidHeight { position: Position, character: char },
+}
+
+fn input_char_to_height(c: char) -> Option<u8> {
     match c {
-        'S' => 1,
-        'E' => 26,
-        'a'..='z' => c as u8 - 96,
-        _ => panic!(),
+        'S' => Some(1),
+        'E' => Some(26),
+        'a'..='z' => Some(c as u8 - 96),
+        _ => None,
     }
 }
 
-fn parse_height_values(input: &str) -> HightMap {
-    let lines: Vec<_> = input.trim().split("\n").collect();
-    lines
-        .iter()
-        .copied()
-        .map(|line| line.chars().map(|c| input_char_to_height(c)).collect())
-        .collect()
-}
+/// Parses the height map together with all start positions (there may be several `S`) and the
+/// goal. Lines are trimmed and blank lines skipped, so line endings and indentation don't matter.
+fn parse_input(input: &str) -> Result<(HightMap, Vec<Position>, Position), ParseError> {
+    let mut map = HightMap::new();
+    let mut starts = Vec::new();
+    let mut goal = None;
 
-fn parse_start_goal_positions(input: &str, columns: usize) -> (Position, Position) {
-    let start_n = input.find('S').unwrap();
-    let goal_n = input.find('E').unwrap();
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let row = map.len();
+        let mut heights = Vec::with_capacity(line.len());
 
-    (
-        (start_n / columns, start_n % columns - start_n / columns),
-        (goal_n / columns, goal_n % columns - goal_n / columns),
-    )
+        for (col, character) in line.chars().enumerate() {
+            let position = (row, col);
+            match (character, goal) {
+                ('S', _) => starts.push(position),
+                ('E', None) => goal = Some(position),
+                ('E', Some(first_goal)) => {
+                    return Err(ParseError::DuplicateGoal(first_goal, position))
+                }
+                _ => {}
+            }
+
+            let height = input_char_to_height(character).ok_or(ParseError::InvalidHeight {
+                position,
+                character,
+            })?;
+            heights.push(height);
+        }
+
+        map.push(heights);
+    }
+
+    if starts.is_empty() {
+        return Err(ParseError::MissingStart);
+    }
+    let goal = goal.ok_or(ParseError::MissingGoal)?;
+
+    Ok((map, starts, goal))
 }
 
-fn parse_input(input: &str) -> (HightMap, (Position, Position)) {
-    let height_map: HightMap = parse_height_values(input);
-    let start_goal_positions = parse_start_goal_positions(input, height_map[0].len());
+/// The cost of moving between two positions on the map, `None` if the rule doesn't allow it.
+fn move_cost(map: &HightMap, rule: &ClimbRule, from: Position, to: Position) -> Option<usize> {
+    let from_height = map.get(from.0).and_then(|row| row.get(from.1))?;
+    let to_height = map.get(to.0).and_then(|row| row.get(to.1))?;
 
-    (height_map, start_goal_positions)
+    rule.cost(*from_height, *to_height)
 }
 
-fn is_successor(map: &HightMap, from: Position, to: Position) -> bool {
-    let from_height_opt = map.get(from.0).and_then(|row| row.get(from.1));
-    let to_height_opt = map.get(to.0).and_then(|row| row.get(to.1));
+/// All positions next to `pos` which are on the map, including the diagonal ones if requested.
+fn neighbours(map: &HightMap, pos: Position, diagonal: bool) -> Vec<Position> {
+    let mut neighbours = vec![];
 
-    match (from_height_opt, to_height_opt) {
-        (Some(from_height), Some(to_height)) => {
-            from_height >= to_height || from_height + 1 == *to_height
+    for (row_offset, col_offset) in [
+        (-1, 0),
+        (1, 0),
+        (0, -1),
+        (0, 1),
+        (-1, -1),
+        (-1, 1),
+        (1, -1),
+        (1, 1),
+    ] {
+        if !diagonal && row_offset != 0 && col_offset != 0 {
+            continue;
         }
-        _ => false,
-    }
-}
 
-fn successors(map: &HightMap, pos: Position) -> Vec<(Position, usize)> {
-    let mut neighbors = vec![];
+        let (Some(row), Some(col)) = (
+            pos.0.checked_add_signed(row_offset),
+            pos.1.checked_add_signed(col_offset),
+        ) else {
+            continue;
+        };
 
-    if pos.0 > 0 && is_successor(map, pos, (pos.0 - 1, pos.1)) {
-        neighbors.push(((pos.0 - 1, pos.1), 1));
-    }
-    if is_successor(map, pos, (pos.0 + 1, pos.1)) {
-        neighbors.push(((pos.0 + 1, pos.1), 1));
+        if row < map.len() && col < map[row].len() {
+            neighbours.push((row, col));
+        }
     }
 
-    if pos.1 > 0 && is_successor(map, pos, (pos.0, pos.1 - 1)) {
-        neighbors.push(((pos.0, pos.1 - 1), 1));
-    }
-    if is_successor(map, pos, (pos.0, pos.1 + 1)) {
-        neighbors.push(((pos.0, pos.1 + 1), 1));
-    }
+    neighbours
+}
 
-    neighbors
+fn successors(map: &HightMap, rule: &ClimbRule, pos: Position) -> Vec<(Position, usize)> {
+    neighbours(map, pos, rule.diagonal)
+        .into_iter()
+        .filter_map(|neighbour| Some((neighbour, move_cost(map, rule, pos, neighbour)?)))
+        .collect()
 }
 
-fn shortest_path_length(map: &HightMap, start: Position, goal: Position) -> Option<usize> {
+/// All positions from which `pos` can be reached in a single move.
+fn predecessors(map: &HightMap, rule: &ClimbRule, pos: Position) -> Vec<(Position, usize)> {
+    neighbours(map, pos, rule.diagonal)
+        .into_iter()
+        .filter_map(|neighbour| Some((neighbour, move_cost(map, rule, neighbour, pos)?)))
+        .collect()
+}
+
+/// The positions visited from start to goal (both included) on a cheapest route, together with
+/// its cost. A* is used while all moves cost the same, Dijkstra otherwise.
+fn shortest_path(
+    map: &HightMap,
+    rule: &ClimbRule,
+    start: Position,
+    goal: Position,
+) -> Option<(Vec<Position>, usize)> {
+    if !rule.is_uniform() {
+        return dijkstra(&start, |p| successors(map, rule, *p), |p| *p == goal);
+    }
+
     astar(
         &start,
-        |p| successors(map, *p),
-        |p| ((p.0 as i32 - goal.0 as i32).abs() + (p.1 as i32 - goal.1 as i32).abs()) as usize,
+        |p| successors(map, rule, *p),
+        |p| {
+            let (rows, cols) = (p.0.abs_diff(goal.0), p.1.abs_diff(goal.1));
+            let moves = if rule.diagonal {
+                rows.max(cols)
+            } else {
+                rows + cols
+            };
+            moves * rule.min_cost()
+        },
         |p| *p == goal,
     )
-    .map(|(_, steps)| steps)
 }
 
-/// Simply route from all possible start locations and take the minimum path length.
-/// Not the smartest solution, but does the trick.
-fn shortest_path_from_any_a_length(map: &HightMap, goal: Position) -> usize {
-    let start_positions: Vec<Position> = map
+/// The cheapest route from any of the start positions.
+fn shortest_path_from_any(
+    map: &HightMap,
+    rule: &ClimbRule,
+    starts: &[Position],
+    goal: Position,
+) -> Option<(Vec<Position>, usize)> {
+    starts
         .iter()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.iter()
-                .copied()
-                .enumerate()
-                .filter(|(_, height)| *height == 1)
-                .map(|(x, _)| (y, x))
-                .collect::<Vec<Position>>()
-        })
-        .collect();
-
-    start_positions
-        .iter()
-        .filter_map(|start| shortest_path_length(map, *start, goal))
+        .filter_map(|start| shortest_path(map, rule, *start, goal))
+        .min_by_key(|(_, cost)| *cost)
+}
+
+/// Cost of reaching the goal from every position, `None` if the goal can't be reached.
+type DistanceField = Vec<Vec<Option<usize>>>;
+
+/// Searches backwards from the goal: a position is expanded to all neighbours that may move onto
+/// it. A single pass yields the cost to reach the goal from every position. While all moves cost
+/// the same this is a breadth-first search, otherwise Dijkstra.
+fn distances_to_goal(map: &HightMap, rule: &ClimbRule, goal: Position) -> DistanceField {
+    let mut distances: DistanceField = map.iter().map(|row| vec![None; row.len()]).collect();
+    distances[goal.0][goal.1] = Some(0);
+
+    if !rule.is_uniform() {
+        for (pos, (_, cost)) in dijkstra_all(&goal, |p| predecessors(map, rule, *p)) {
+            distances[pos.0][pos.1] = Some(cost);
+        }
+        return distances;
+    }
+
+    let mut queue = VecDeque::from([goal]);
+    while let Some(pos) = queue.pop_front() {
+        let distance = distances[pos.0][pos.1].unwrap();
+        for (neighbour, cost) in predecessors(map, rule, pos) {
+            if distances[neighbour.0][neighbour.1].is_none() {
+                distances[neighbour.0][neighbour.1] = Some(distance + cost);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    distances
+}
+
+fn shortest_path_from_any_a_length(map: &HightMap, rule: &ClimbRule, goal: Position) -> usize {
+    let distances = distances_to_goal(map, rule, goal);
+
+    map.iter()
+        .flatten()
+        .zip(distances.iter().flatten())
+        .filter(|(height, _)| **height == 1)
+        .filter_map(|(_, distance)| *distance)
         .min()
         .unwrap_or(usize::MAX)
 }
 
+/// Usage: `day-12 [--path] [--profile] [<climb rule options>]`, see [`ClimbRule::from_args`] for
+/// the options changing the movement rules.
 fn main() {
     let mut f = File::open("input.txt").unwrap();
     let mut input = String::new();
     f.read_to_string(&mut input).unwrap();
 
-    let (map, (start, goal)) = parse_input(&input);
-    println!("Part One: {:?}", shortest_path_length(&map, start, goal));
-    println!("Part Two:\n{}", shortest_path_from_any_a_length(&map, goal));
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let rule = ClimbRule::from_args(&args).unwrap();
+
+    let (map, starts, goal) = parse_input(&input).unwrap();
+    let part_one = shortest_path_from_any(&map, &rule, &starts, goal);
+    println!("Part One: {:?}", part_one.as_ref().map(|(_, cost)| cost));
+    println!(
+        "Part Two:\n{}",
+        shortest_path_from_any_a_length(&map, &rule, goal)
+    );
+
+    if let Some((path, _)) = part_one {
+        if args.iter().any(|arg| arg == "--path") {
+            println!("\n{}", render::render_path(&map, &path));
+        }
+        if args.iter().any(|arg| arg == "--profile") {
+            println!("\n{}", render::render_elevation_profile(&map, &path));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -127,32 +251,126 @@ abdefghi";
 
     #[test]
     fn part1_works() {
-        let (map, (start, goal)) = parse_input(EXAMPLE_INPUT);
-        let result = shortest_path_length(&map, start, goal);
+        let (map, starts, goal) = parse_input(EXAMPLE_INPUT).unwrap();
+        let start = starts[0];
+        let result = shortest_path(&map, &ClimbRule::default(), start, goal).map(|(_, cost)| cost);
         assert_eq!(result, Some(31));
     }
 
     #[test]
     fn part2_works() {
-        let (map, (_, goal)) = parse_input(EXAMPLE_INPUT);
-        let result = shortest_path_from_any_a_length(&map, goal);
+        let (map, _, goal) = parse_input(EXAMPLE_INPUT).unwrap();
+        let result = shortest_path_from_any_a_length(&map, &ClimbRule::default(), goal);
         assert_eq!(result, 29);
     }
 
+    #[test]
+    fn distances_to_goal_works() {
+        let (map, starts, goal) = parse_input(EXAMPLE_INPUT).unwrap();
+        let start = starts[0];
+        let distances = distances_to_goal(&map, &ClimbRule::default(), goal);
+
+        assert_eq!(distances[goal.0][goal.1], Some(0));
+        assert_eq!(distances[start.0][start.1], Some(31));
+        assert_eq!(distances[4][0], Some(29));
+    }
+
+    #[test]
+    fn distances_to_goal_unreachable_works() {
+        let map = vec![vec![1, 3, 2]];
+        let distances = distances_to_goal(&map, &ClimbRule::default(), (0, 2));
+        assert_eq!(distances, vec![vec![None, Some(1), Some(0)]]);
+    }
+
+    #[test]
+    fn descending_costs_two_works() {
+        let map = vec![vec![1, 2, 2], vec![1, 1, 1], vec![1, 1, 1]];
+        let rule = ClimbRule {
+            descent_cost: 2,
+            ..ClimbRule::default()
+        };
+
+        // Avoids the hill in the top row, although both routes have the same number of steps.
+        let (path, cost) = shortest_path(&map, &rule, (0, 0), (1, 2)).unwrap();
+        assert_eq!(cost, 3);
+        assert_eq!(path, vec![(0, 0), (1, 0), (1, 1), (1, 2)]);
+
+        let distances = distances_to_goal(&map, &rule, (1, 2));
+        assert_eq!(distances[0][0], Some(3));
+        assert_eq!(distances[0][2], Some(2));
+    }
+
+    #[test]
+    fn diagonal_moves_work() {
+        let (map, starts, goal) = parse_input(EXAMPLE_INPUT).unwrap();
+        let start = starts[0];
+        let rule = ClimbRule {
+            diagonal: true,
+            ..ClimbRule::default()
+        };
+
+        let (_, straight) = shortest_path(&map, &ClimbRule::default(), start, goal).unwrap();
+        let (_, diagonal) = shortest_path(&map, &rule, start, goal).unwrap();
+        assert!(diagonal < straight);
+
+        let distances = distances_to_goal(&map, &rule, goal);
+        assert_eq!(distances[start.0][start.1], Some(diagonal));
+    }
+
     #[test]
     fn input_char_to_height_works() {
-        assert_eq!(input_char_to_height('a'), 1);
-        assert_eq!(input_char_to_height('c'), 3);
-        assert_eq!(input_char_to_height('z'), 26);
+        assert_eq!(input_char_to_height('a'), Some(1));
+        assert_eq!(input_char_to_height('c'), Some(3));
+        assert_eq!(input_char_to_height('z'), Some(26));
 
-        assert_eq!(input_char_to_height('S'), 1);
-        assert_eq!(input_char_to_height('E'), 26);
+        assert_eq!(input_char_to_height('S'), Some(1));
+        assert_eq!(input_char_to_height('E'), Some(26));
+        assert_eq!(input_char_to_height('A'), None);
     }
 
     #[test]
-    fn parse_start_goal_positions_works() {
-        let (start, goal) = parse_start_goal_positions(EXAMPLE_INPUT, 8);
-        assert_eq!(start, (0, 0));
+    fn parse_input_works() {
+        let (map, starts, goal) = parse_input(EXAMPLE_INPUT).unwrap();
+        assert_eq!(map.len(), 5);
+        assert_eq!(starts, vec![(0, 0)]);
         assert_eq!(goal, (2, 5));
     }
+
+    #[test]
+    fn parse_input_tolerates_formatting_works() {
+        let input = "\r\n  Sab\r\n  abcdE\r\nSa\r\n";
+        let (map, starts, goal) = parse_input(input).unwrap();
+        assert_eq!(map, vec![vec![1, 1, 2], vec![1, 2, 3, 4, 26], vec![1, 1]]);
+        assert_eq!(starts, vec![(0, 0), (2, 0)]);
+        assert_eq!(goal, (1, 4));
+    }
+
+    #[test]
+    fn shortest_path_from_any_works() {
+        let input = EXAMPLE_INPUT.replace("abdefghi", "Sbdefghi");
+        let (map, starts, goal) = parse_input(&input).unwrap();
+        assert_eq!(starts, vec![(0, 0), (4, 0)]);
+
+        let (path, cost) =
+            shortest_path_from_any(&map, &ClimbRule::default(), &starts, goal).unwrap();
+        assert_eq!(cost, 29);
+        assert_eq!(path[0], (4, 0));
+    }
+
+    #[test]
+    fn parse_input_errors_work() {
+        assert_eq!(parse_input("abE"), Err(ParseError::MissingStart));
+        assert_eq!(parse_input("Sab"), Err(ParseError::MissingGoal));
+        assert_eq!(
+            parse_input("SEa\naaE"),
+            Err(ParseError::DuplicateGoal((0, 1), (1, 2)))
+        );
+        assert_eq!(
+            parse_input("Sa\na-E"),
+            Err(ParseError::InvalidHeight {
+                position: (1, 1),
+                character: '-'
+            })
+        );
+    }
 }
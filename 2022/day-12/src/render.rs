@@ -0,0 +1,93 @@
+use crate::{HightMap, Position};
+
+/// Draws the path like the puzzle description does: every position on the path points towards
+/// the next one with `>`, `v`, `<` or `^` (and arrows for diagonal moves), the goal is marked
+/// with `E` and everything else is `.`.
+pub fn render_path(map: &HightMap, path: &[Position]) -> String {
+    let mut canvas: Vec<Vec<char>> = map.iter().map(|row| vec!['.'; row.len()]).collect();
+
+    for step in path.windows(2) {
+        let (from, to) = (step[0], step[1]);
+        canvas[from.0][from.1] = match (
+            to.0 as isize - from.0 as isize,
+            to.1 as isize - from.1 as isize,
+        ) {
+            (0, 1) => '>',
+            (1, 0) => 'v',
+            (0, -1) => '<',
+            (-1, 0) => '^',
+            (-1, 1) => '↗',
+            (1, 1) => '↘',
+            (1, -1) => '↙',
+            (-1, -1) => '↖',
+            _ => '?',
+        };
+    }
+    if let Some(goal) = path.last() {
+        canvas[goal.0][goal.1] = 'E';
+    }
+
+    canvas
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The heights along the path, one line per step with the step number, the height as letter and a
+/// bar as long as the height.
+pub fn render_elevation_profile(map: &HightMap, path: &[Position]) -> String {
+    path.iter()
+        .enumerate()
+        .map(|(step, pos)| {
+            let height = map[pos.0][pos.1];
+            let letter = (b'a' + height - 1) as char;
+            format!("{:>4} {} {}", step, letter, "#".repeat(height as usize))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, shortest_path, ClimbRule};
+
+    #[test]
+    fn render_path_works() {
+        let (map, starts, goal) = parse_input(
+            "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi",
+        )
+        .unwrap();
+        let (path, _) = shortest_path(&map, &ClimbRule::default(), starts[0], goal).unwrap();
+
+        let rendered = render_path(&map, &path);
+        assert_eq!(rendered.matches(['>', 'v', '<', '^']).count(), 31);
+        assert_eq!(rendered.lines().nth(2).unwrap().chars().nth(5), Some('E'));
+        assert!(rendered.starts_with(['>', 'v']));
+    }
+
+    #[test]
+    fn render_path_directions_work() {
+        let map = vec![vec![1, 1], vec![1, 1]];
+        let path = vec![(0, 0), (0, 1), (1, 1), (1, 0)];
+        assert_eq!(render_path(&map, &path), ">v\nE<");
+
+        let path = vec![(1, 0), (0, 0)];
+        assert_eq!(render_path(&map, &path), "E.\n^.");
+    }
+
+    #[test]
+    fn render_elevation_profile_works() {
+        let map = vec![vec![1, 2, 3]];
+        let path = vec![(0, 0), (0, 1), (0, 2)];
+        assert_eq!(
+            render_elevation_profile(&map, &path),
+            "   0 a #\n   1 b ##\n   2 c ###"
+        );
+    }
+}
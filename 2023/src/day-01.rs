@@ -1,75 +1,295 @@
-use regex::Regex;
+use std::collections::HashMap;
 use std::fs::read_to_string;
 
+/// A line of the calibration document without any digit, 1-based.
+#[derive(Debug, PartialEq)]
+struct NoDigitError {
+    line: usize,
+}
+
+const NUMERIC_DIGITS: [(&str, usize); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+const SPELLED_DIGITS: [(&str, usize); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+#[derive(Debug, PartialEq)]
+enum VocabularyError {
+    /// A line without `=` between word and value, 1-based.
+    MissingValue {
+        line: usize,
+    },
+    /// A value which isn't a single digit from 0 to 9.
+    InvalidValue {
+        line: usize,
+    },
+    EmptyWord {
+        line: usize,
+    },
+}
+
+/// The words that count as digits, each with the digit's value from 0 to 9. Words may contain
+/// spaces, so phrases can stand for a digit as well.
+#[derive(Debug, PartialEq)]
+struct Vocabulary {
+    words: Vec<(String, usize)>,
+    ignore_case: bool,
+}
+
+impl Vocabulary {
+    fn from_table(table: &[(&str, usize)]) -> Vocabulary {
+        Vocabulary {
+            words: table
+                .iter()
+                .map(|(word, value)| (word.to_string(), *value))
+                .collect(),
+            ignore_case: false,
+        }
+    }
+
+    /// The numeric digits only, as in part 1.
+    fn numeric() -> Vocabulary {
+        Vocabulary::from_table(&NUMERIC_DIGITS)
+    }
+
+    /// The numeric digits and the English words `one` to `nine`, as in part 2.
+    fn english() -> Vocabulary {
+        let mut vocabulary = Vocabulary::numeric();
+        vocabulary.extend(&Vocabulary::from_table(&SPELLED_DIGITS));
+        vocabulary
+    }
+
+    /// Reads one `word = value` per line, ignoring empty lines and lines starting with `#`.
+    fn parse(definition: &str) -> Result<Vocabulary, VocabularyError> {
+        let words = definition
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(line_number, line)| {
+                let (word, value) = line
+                    .rsplit_once('=')
+                    .ok_or(VocabularyError::MissingValue { line: line_number })?;
+                let word = word.trim();
+                if word.is_empty() {
+                    return Err(VocabularyError::EmptyWord { line: line_number });
+                }
+                let value = value
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|value| *value <= 9)
+                    .ok_or(VocabularyError::InvalidValue { line: line_number })?;
+
+                Ok((word.to_string(), value))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Vocabulary {
+            words,
+            ignore_case: false,
+        })
+    }
+
+    fn extend(&mut self, other: &Vocabulary) {
+        self.words.extend(other.words.iter().cloned());
+    }
+
+    fn ignoring_case(self) -> Vocabulary {
+        Vocabulary {
+            ignore_case: true,
+            ..self
+        }
+    }
+
+    fn scanner(&self) -> scanner::Scanner {
+        let words: Vec<_> = self
+            .words
+            .iter()
+            .map(|(word, value)| (self.normalize(word), *value))
+            .collect();
+        scanner::Scanner::new(words.iter().map(|(word, value)| (word.as_str(), *value)))
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        match self.ignore_case {
+            true => text.to_lowercase(),
+            false => text.to_string(),
+        }
+    }
+}
+
+/// Usage: `day-01 [--vocabulary <path> [--ignore-case]]`. With a vocabulary file of `word = value`
+/// lines, the calibration is done with its words in addition to the numeric digits, matching them
+/// regardless of case with `--ignore-case`. The puzzle's parts are always case sensitive, so
+/// `--ignore-case` requires `--vocabulary`.
 fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map(|index| match args.get(index + 1) {
+                Some(value) => value.as_str(),
+                None => panic!("{name} requires a value"),
+            })
+    };
+    let ignore_case = args.iter().any(|arg| arg == "--ignore-case");
+    assert!(
+        !ignore_case || arg_value("--vocabulary").is_some(),
+        "--ignore-case requires --vocabulary"
+    );
+
     let input = read_to_string("./inputs/day-01.txt").expect("read input");
-    println!("Solution Part 1: {}", solve_part1(&input));
-    println!("Solution Part 2: {}", solve_part2(&input));
+
+    if let Some(path) = arg_value("--vocabulary") {
+        let definition = read_to_string(path).expect("read vocabulary");
+        let mut vocabulary = Vocabulary::numeric();
+        vocabulary.extend(&Vocabulary::parse(&definition).unwrap());
+        if ignore_case {
+            vocabulary = vocabulary.ignoring_case();
+        }
+        println!(
+            "Solution: {}",
+            sum_calibration_values(&input, &vocabulary).unwrap()
+        );
+        return;
+    }
+
+    println!("Solution Part 1: {}", solve_part1(&input).unwrap());
+    println!("Solution Part 2: {}", solve_part2(&input).unwrap());
 }
 
-fn solve_part1(input: &str) -> usize {
-    let re = Regex::new(r"\d").unwrap();
+fn solve_part1(input: &str) -> Result<usize, NoDigitError> {
+    sum_calibration_values(input, &Vocabulary::numeric())
+}
 
+fn solve_part2(input: &str) -> Result<usize, NoDigitError> {
+    sum_calibration_values(input, &Vocabulary::english())
+}
+
+fn sum_calibration_values(input: &str, vocabulary: &Vocabulary) -> Result<usize, NoDigitError> {
+    let scanner = vocabulary.scanner();
     input
         .lines()
-        .into_iter()
-        .map(|line| {
-            let captures: Vec<usize> = re
-                .captures_iter(line)
-                .map(|c| c.get(0).unwrap().as_str())
-                .map(|s| s.parse().unwrap())
-                .collect();
-            let first: &usize = captures.first().unwrap();
-            let last: &usize = captures.last().unwrap();
-
-            first * 10 + last
+        .enumerate()
+        .map(|(index, line)| {
+            let line = vocabulary.normalize(line);
+            let first = scanner
+                .first(&line)
+                .ok_or(NoDigitError { line: index + 1 })?;
+            let last = scanner
+                .last(&line)
+                .ok_or(NoDigitError { line: index + 1 })?;
+
+            Ok(first * 10 + last)
         })
         .sum()
 }
 
-fn solve_part2(input: &str) -> usize {
-    let re = Regex::new(r"one|two|three|four|five|six|seven|eight|nine|\d").unwrap();
-
-    fn parse_capture(s: &str) -> usize {
-        match s {
-            "one" => 1,
-            "two" => 2,
-            "three" => 3,
-            "four" => 4,
-            "five" => 5,
-            "six" => 6,
-            "seven" => 7,
-            "eight" => 8,
-            "nine" => 9,
-            _ => s.parse().unwrap(),
-        }
+mod scanner {
+    use super::HashMap;
+
+    #[derive(Default)]
+    struct Node {
+        children: HashMap<u8, usize>,
+        value: Option<usize>,
     }
 
-    fn line_captures(line: &str, re: &Regex) -> Vec<usize> {
-        let mut res = Vec::new();
-        for i in 0..line.len() {
-            if let Some(capture) = re.captures_at(line, i) {
-                if capture.get(0).unwrap().start() != i {
-                    continue;
+    /// A byte trie over a set of words, each mapped to a value.
+    struct Trie {
+        nodes: Vec<Node>,
+    }
+
+    impl Trie {
+        fn new(words: impl Iterator<Item = (Vec<u8>, usize)>) -> Trie {
+            let mut nodes = vec![Node::default()];
+            for (word, value) in words {
+                let mut current = 0;
+                for byte in word {
+                    current = match nodes[current].children.get(&byte) {
+                        Some(next) => *next,
+                        None => {
+                            nodes.push(Node::default());
+                            let next = nodes.len() - 1;
+                            nodes[current].children.insert(byte, next);
+                            next
+                        }
+                    };
                 }
-                res.push(parse_capture(capture.get(0).unwrap().as_str()));
+                nodes[current].value = Some(value);
             }
+
+            Trie { nodes }
         }
 
-        res
+        /// The value of the longest word the bytes start with.
+        fn longest_match(&self, bytes: impl Iterator<Item = u8>) -> Option<usize> {
+            let mut current = 0;
+            let mut value = None;
+            for byte in bytes {
+                match self.nodes[current].children.get(&byte) {
+                    Some(next) => current = *next,
+                    None => break,
+                }
+                value = self.nodes[current].value.or(value);
+            }
+
+            value
+        }
     }
 
-    input
-        .lines()
-        .into_iter()
-        .map(|line| {
-            let captures: Vec<usize> = line_captures(line, &re);
-            let first: &usize = captures.first().unwrap();
-            let last: &usize = captures.last().unwrap();
+    /// Finds the first and last of a set of words in a line. Words are matched at every position,
+    /// so overlapping words like `eightwo` are found both from the start and from the end.
+    pub struct Scanner {
+        forward: Trie,
+        /// The words reversed, to match them ending at a position while walking back.
+        backward: Trie,
+    }
 
-            first * 10 + last
-        })
-        .sum()
+    impl Scanner {
+        pub fn new<'a>(words: impl Iterator<Item = (&'a str, usize)> + Clone) -> Scanner {
+            let words = words.map(|(word, value)| (word.as_bytes(), value));
+            Scanner {
+                forward: Trie::new(words.clone().map(|(word, value)| (word.to_vec(), value))),
+                backward: Trie::new(
+                    words.map(|(word, value)| (word.iter().rev().copied().collect(), value)),
+                ),
+            }
+        }
+
+        pub fn first(&self, line: &str) -> Option<usize> {
+            let bytes = line.as_bytes();
+            (0..bytes.len())
+                .find_map(|start| self.forward.longest_match(bytes[start..].iter().copied()))
+        }
+
+        pub fn last(&self, line: &str) -> Option<usize> {
+            let bytes = line.as_bytes();
+            (1..=bytes.len()).rev().find_map(|end| {
+                self.backward
+                    .longest_match(bytes[..end].iter().rev().copied())
+            })
+        }
+    }
 }
 
 #[cfg(test)]
@@ -83,14 +303,14 @@ pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet
 "#;
-        let solution = solve_part1(&input);
-        assert_eq!(solution, 142);
+        let solution = solve_part1(input);
+        assert_eq!(solution, Ok(142));
     }
 
     #[test]
     fn part1_test1() {
         let solution = solve_part1("769twotwo6rv9");
-        assert_eq!(solution, 79);
+        assert_eq!(solution, Ok(79));
     }
 
     #[test]
@@ -103,21 +323,95 @@ xtwone3four
 zoneight234
 7pqrstsixteen
 "#;
-        let solution = solve_part2(&input);
-        assert_eq!(solution, 281);
+        let solution = solve_part2(input);
+        assert_eq!(solution, Ok(281));
     }
 
     #[test]
     fn part2_test1() {
         let input = r#"eightwo1eightwo"#;
-        let solution = solve_part2(&input);
-        assert_eq!(solution, 82);
+        let solution = solve_part2(input);
+        assert_eq!(solution, Ok(82));
     }
 
     #[test]
     fn part2_test2() {
         let input = r#"hclv99two89nsfdfour4"#;
-        let solution = solve_part2(&input);
-        assert_eq!(solution, 94);
+        let solution = solve_part2(input);
+        assert_eq!(solution, Ok(94));
+    }
+
+    #[test]
+    fn overlapping_words_work() {
+        assert_eq!(solve_part2("eightwo"), Ok(82));
+        assert_eq!(solve_part2("xoneightx"), Ok(18));
+        assert_eq!(solve_part2("twone"), Ok(21));
+    }
+
+    #[test]
+    fn vocabulary_parse_works() {
+        let vocabulary = Vocabulary::parse("# German\neins = 1\n\nzwei=2\nein paar = 3").unwrap();
+        assert_eq!(
+            vocabulary.words,
+            vec![
+                ("eins".to_string(), 1),
+                ("zwei".to_string(), 2),
+                ("ein paar".to_string(), 3)
+            ]
+        );
+
+        assert_eq!(
+            Vocabulary::parse("eins = 1\nzwei"),
+            Err(VocabularyError::MissingValue { line: 2 })
+        );
+        assert_eq!(
+            Vocabulary::parse("eins = x"),
+            Err(VocabularyError::InvalidValue { line: 1 })
+        );
+        // Digits are combined as tens and ones, so a word can't stand for several digits
+        assert_eq!(
+            Vocabulary::parse("eins = 1\nein dutzend = 12"),
+            Err(VocabularyError::InvalidValue { line: 2 })
+        );
+        assert_eq!(
+            Vocabulary::parse(" = 1"),
+            Err(VocabularyError::EmptyWord { line: 1 })
+        );
+    }
+
+    #[test]
+    fn custom_vocabularies_work() {
+        let mut german = Vocabulary::numeric();
+        german.extend(&Vocabulary::parse("null = 0\neins = 1\nzwei = 2\ndrei = 3").unwrap());
+        assert_eq!(
+            sum_calibration_values("xdreinullx\nzweins", &german),
+            Ok(30 + 21)
+        );
+
+        let zero = Vocabulary::parse("zero = 0\nseven = 7").unwrap();
+        assert_eq!(sum_calibration_values("sevenzero", &zero), Ok(70));
+
+        let multi_word = Vocabulary::parse("one = 1\nhalf a dozen = 6").unwrap();
+        assert_eq!(
+            sum_calibration_values("half a dozen one", &multi_word),
+            Ok(61)
+        );
+    }
+
+    #[test]
+    fn ignore_case_works() {
+        assert_eq!(solve_part2("OneTWO"), Err(NoDigitError { line: 1 }));
+
+        let vocabulary = Vocabulary::english().ignoring_case();
+        assert_eq!(
+            sum_calibration_values("OneTWO\nxSEVEN", &vocabulary),
+            Ok(12 + 77)
+        );
+    }
+
+    #[test]
+    fn lines_without_digits_fail() {
+        assert_eq!(solve_part1("1a\nabc\n2"), Err(NoDigitError { line: 2 }));
+        assert_eq!(solve_part2("one\n2\nxyz"), Err(NoDigitError { line: 3 }));
     }
 }
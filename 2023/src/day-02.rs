@@ -1,89 +1,111 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::read_to_string;
 
-use itertools::izip;
-
-#[derive(Debug, PartialEq)]
-enum Cube {
-    Red,
-    Green,
-    Blue,
-}
+/// Cube counts by colour, for draws as well as for bags.
+type Cubes = BTreeMap<String, usize>;
 
 #[derive(Debug)]
 struct Draw {
-    cubes: Vec<(Cube, usize)>,
+    cubes: Cubes,
 }
 
 #[derive(Debug)]
 struct Game {
+    id: usize,
     draws: Vec<Draw>,
 }
 
+/// Two games share the ID, so they can't be told apart by it.
+#[derive(Debug, PartialEq)]
+struct DuplicateGameId {
+    id: usize,
+}
+
+/// Usage: `day-02 [--bag <colour>=<count>,...]`, the bag defaulting to the puzzle's 12 red, 13
+/// green and 14 blue cubes. Colours missing from the bag aren't in it at all.
 fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map(|index| match args.get(index + 1) {
+                Some(value) => value.as_str(),
+                None => panic!("{name} requires a value"),
+            })
+    };
+    let bag = arg_value("--bag").map_or_else(puzzle_bag, parse_bag);
+
     let input = read_to_string("./inputs/day-02.txt").expect("read input");
-    println!("Solution Part 1: {}", solve_part1(&input));
-    println!("Solution Part 2: {}", solve_part2(&input));
+    println!("Solution Part 1: {}", solve_part1(&input, &bag).unwrap());
+    println!("Solution Part 2: {}", solve_part2(&input).unwrap());
 }
 
-fn solve_part1(input: &str) -> usize {
-    fn is_game_possible(game: &Game) -> bool {
-        // limits: 12 red cubes, 13 green cubes, and 14 blue cubes
-        for draw in &game.draws {
-            for cube in &draw.cubes {
-                let limited_exceeded = match cube {
-                    (Cube::Red, a) => *a > 12,
-                    (Cube::Green, a) => *a > 13,
-                    (Cube::Blue, a) => *a > 14,
-                };
-                if limited_exceeded {
-                    return false;
-                }
-            }
-        }
+/// The bag of part 1: 12 red cubes, 13 green cubes, and 14 blue cubes.
+fn puzzle_bag() -> Cubes {
+    parse_bag("red=12,green=13,blue=14")
+}
 
-        return true;
-    }
+fn parse_bag(input: &str) -> Cubes {
+    input
+        .split(',')
+        .map(|entry| {
+            let (colour, count) = entry.split_once('=').unwrap();
+            (colour.trim().to_string(), count.trim().parse().unwrap())
+        })
+        .collect()
+}
 
-    let games = parser::parse(input);
-    games
-        .iter()
-        .enumerate()
-        .filter(|(_, g)| is_game_possible(g))
-        .map(|(i, _)| i + 1)
-        .sum()
+fn solve_part1(input: &str, bag: &Cubes) -> Result<usize, DuplicateGameId> {
+    let games = parser::parse(input)?;
+    Ok(possible_games(&games, bag).map(|game| game.id).sum())
+}
+
+fn solve_part2(input: &str) -> Result<usize, DuplicateGameId> {
+    let games = parser::parse(input)?;
+    Ok(minimum_bags(&games).values().map(power).sum())
 }
 
-fn solve_part2(input: &str) -> usize {
-    fn max_count(cube: Cube, game: &Game) -> usize {
-        game.draws
+fn is_game_possible(game: &Game, bag: &Cubes) -> bool {
+    game.draws.iter().all(|draw| {
+        draw.cubes
             .iter()
-            .flat_map(|draw| draw.cubes.iter().find(|(c, _)| *c == cube))
-            .map(|(_, count)| count)
-            .max()
-            .copied()
-            .unwrap_or(0)
-    }
+            .all(|(colour, count)| *count <= bag.get(colour).copied().unwrap_or(0))
+    })
+}
 
-    let games = parser::parse(input);
+/// The games that could have been played with the cubes of `bag`.
+fn possible_games<'a>(games: &'a [Game], bag: &'a Cubes) -> impl Iterator<Item = &'a Game> {
+    games.iter().filter(|game| is_game_possible(game, bag))
+}
 
-    let reds: Vec<usize> = games
-        .iter()
-        .map(|game| max_count(Cube::Red, game))
-        .collect();
-    let greens: Vec<usize> = games
+/// The fewest cubes of each colour that make every game possible, by game ID. Every colour seen in
+/// any of the games is included, with a count of zero for games it doesn't appear in.
+fn minimum_bags(games: &[Game]) -> BTreeMap<usize, Cubes> {
+    let colours: BTreeSet<_> = games
         .iter()
-        .map(|game| max_count(Cube::Green, game))
+        .flat_map(|game| game.draws.iter())
+        .flat_map(|draw| draw.cubes.keys())
         .collect();
-    let blues: Vec<usize> = games
+
+    games
         .iter()
-        .map(|game| max_count(Cube::Blue, game))
-        .collect();
+        .map(|game| {
+            let mut bag: Cubes = colours
+                .iter()
+                .map(|colour| (colour.to_string(), 0))
+                .collect();
+            for (colour, count) in game.draws.iter().flat_map(|draw| &draw.cubes) {
+                let minimum = bag.get_mut(colour).unwrap();
+                *minimum = (*minimum).max(*count);
+            }
+            (game.id, bag)
+        })
+        .collect()
+}
 
-    let mut res = 0;
-    for (r, g, b) in izip!(reds, greens, blues) {
-        res += r * g * b;
-    }
-    res
+/// The numbers of cubes of every colour multiplied together.
+fn power(bag: &Cubes) -> usize {
+    bag.values().product()
 }
 
 #[cfg(test)]
@@ -98,8 +120,8 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
 "#;
-        let solution = solve_part1(&input);
-        assert_eq!(solution, 8);
+        let solution = solve_part1(input, &puzzle_bag());
+        assert_eq!(solution, Ok(8));
     }
 
     #[test]
@@ -110,49 +132,93 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green            
 "#;
-        let solution = solve_part2(&input);
-        assert_eq!(solution, 2286);
+        let solution = solve_part2(input);
+        assert_eq!(solution, Ok(2286));
+    }
+
+    #[test]
+    fn queries_work() {
+        let input = r#"Game 7: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 12: 1 blue, 2 purple; 3 green, 4 blue
+Game 3: 8 green, 6 blue, 20 red
+"#;
+        let games = parser::parse(input).unwrap();
+        assert_eq!(
+            games.iter().map(|game| game.id).collect::<Vec<_>>(),
+            vec![7, 12, 3]
+        );
+
+        let possible: Vec<_> = possible_games(&games, &puzzle_bag())
+            .map(|game| game.id)
+            .collect();
+        assert_eq!(possible, vec![7]);
+
+        let bag = parse_bag("red=20,green=8,blue=6,purple=2");
+        let possible: Vec<_> = possible_games(&games, &bag).map(|game| game.id).collect();
+        assert_eq!(possible, vec![7, 12, 3]);
+
+        let bags = minimum_bags(&games);
+        assert_eq!(bags[&12], parse_bag("red=0,green=3,blue=4,purple=2"));
+        assert_eq!(power(&bags[&7]), 0);
+        assert_eq!(power(&parse_bag("red=4,green=2,blue=6")), 48);
+    }
+
+    #[test]
+    fn repeated_colours_are_added() {
+        let games = parser::parse("Game 1: 1 red, 2 red, 3 blue; 4 red").unwrap();
+        assert_eq!(games[0].draws[0].cubes, parse_bag("red=3,blue=3"));
+        assert_eq!(solve_part2("Game 1: 1 red, 2 red, 3 blue"), Ok(9));
+    }
+
+    #[test]
+    fn duplicate_game_ids_fail() {
+        let input = "Game 1: 1 red\nGame 2: 2 red\nGame 1: 3 blue";
+        assert_eq!(
+            solve_part1(input, &puzzle_bag()),
+            Err(DuplicateGameId { id: 1 })
+        );
+        assert_eq!(solve_part2(input), Err(DuplicateGameId { id: 1 }));
     }
 }
 
 mod parser {
     use super::*;
 
-    pub(super) fn parse(input: &str) -> Vec<Game> {
-        input.lines().map(|l| parse_line(l)).collect()
+    pub(super) fn parse(input: &str) -> Result<Vec<Game>, DuplicateGameId> {
+        let games: Vec<_> = input.lines().map(parse_line).collect();
+
+        let mut ids = BTreeSet::new();
+        match games.iter().find(|game| !ids.insert(game.id)) {
+            Some(game) => Err(DuplicateGameId { id: game.id }),
+            None => Ok(games),
+        }
     }
 
     fn parse_line(input: &str) -> Game {
-        let (_, game_part) = input.split_once(":").unwrap();
+        let (game_part, draws_part) = input.split_once(':').unwrap();
+        let id = game_part.trim().strip_prefix("Game ").unwrap();
         Game {
-            draws: parse_draws(game_part.trim()),
+            id: id.parse().unwrap(),
+            draws: parse_draws(draws_part.trim()),
         }
     }
 
     fn parse_draws(input: &str) -> Vec<Draw> {
-        input.split(";").map(|d| parse_draw(d.trim())).collect()
+        input.split(';').map(|d| parse_draw(d.trim())).collect()
     }
 
+    /// A colour listed more than once in a draw counts with all of its cubes.
     fn parse_draw(input: &str) -> Draw {
-        Draw {
-            cubes: input
-                .split(",")
-                .map(|c| parse_count_and_cube(c.trim()))
-                .collect(),
+        let mut cubes = Cubes::new();
+        for (colour, count) in input.split(',').map(|c| parse_count_and_cube(c.trim())) {
+            *cubes.entry(colour).or_insert(0) += count;
         }
+        Draw { cubes }
     }
 
-    fn parse_count_and_cube(input: &str) -> (Cube, usize) {
-        let (count_str, cube_str) = input.split_once(" ").unwrap();
-
-        let cube = match cube_str {
-            "red" => Cube::Red,
-            "green" => Cube::Green,
-            "blue" => Cube::Blue,
-            _ => panic!("{}", count_str),
-        };
-        let count = count_str.parse().unwrap();
+    fn parse_count_and_cube(input: &str) -> (String, usize) {
+        let (count_str, colour) = input.split_once(' ').unwrap();
 
-        (cube, count)
+        (colour.trim().to_string(), count_str.parse().unwrap())
     }
 }
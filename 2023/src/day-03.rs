@@ -1,4 +1,4 @@
-use std::fs::read_to_string;
+use std::{fs::read_to_string, time::Instant};
 
 use itertools::Itertools;
 
@@ -23,8 +23,102 @@ struct Blueprint {
     symbols: Vec<Symbol>,
 }
 
+/// Maps every cell of the schematic to the number occupying it, so the numbers around a symbol
+/// are found by looking at its eight neighbouring cells only.
+struct BlueprintIndex {
+    width: usize,
+    cells: Vec<Option<usize>>,
+}
+
+impl BlueprintIndex {
+    fn new(blueprint: &Blueprint) -> BlueprintIndex {
+        let width = blueprint
+            .numbers
+            .iter()
+            .map(|num| num.end + 1)
+            .chain(blueprint.symbols.iter().map(|sym| sym.column + 1))
+            .max()
+            .unwrap_or(0);
+        let height = blueprint
+            .numbers
+            .iter()
+            .map(|num| num.line + 1)
+            .chain(blueprint.symbols.iter().map(|sym| sym.line + 1))
+            .max()
+            .unwrap_or(0);
+
+        let mut cells = vec![None; width * height];
+        for (index, num) in blueprint.numbers.iter().enumerate() {
+            for column in num.start..=num.end {
+                cells[num.line * width + column] = Some(index);
+            }
+        }
+
+        BlueprintIndex { width, cells }
+    }
+
+    fn number_at(&self, line: usize, column: usize) -> Option<usize> {
+        if column >= self.width {
+            return None;
+        }
+        self.cells
+            .get(line * self.width + column)
+            .copied()
+            .flatten()
+    }
+
+    /// Indices of the distinct numbers adjacent to the symbol, diagonally included.
+    fn numbers_around(&self, sym: &Symbol) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for line in sym.line.saturating_sub(1)..=sym.line + 1 {
+            for column in sym.column.saturating_sub(1)..=sym.column + 1 {
+                if let Some(index) = self.number_at(line, column) {
+                    if !indices.contains(&index) {
+                        indices.push(index);
+                    }
+                }
+            }
+        }
+        indices
+    }
+}
+
+/// Usage: `day-03 [--annotate [--no-color]] [--gears] [--bench <size> [--naive]]`.
+/// `--annotate` reprints the schematic with part numbers and gears highlighted, `--gears` lists
+/// every gear. The benchmark solves a generated schematic of `size`×`size` cells, with `--naive`
+/// also comparing every number with every symbol.
 fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .map(|index| match args.get(index + 1) {
+                Some(value) => value.as_str(),
+                None => panic!("{name} requires a value"),
+            })
+    };
+    if let Some(size) = arg_value("--bench") {
+        let naive = args.iter().any(|arg| arg == "--naive");
+        bench(size.parse().unwrap(), naive);
+        return;
+    }
+
     let input = read_to_string("./inputs/day-03.txt").expect("read input");
+    let has_flag = |name: &str| args.iter().any(|arg| arg == name);
+    if has_flag("--annotate") || has_flag("--gears") {
+        let blueprint = parser::parse(&input);
+        if has_flag("--annotate") {
+            println!(
+                "{}",
+                render::annotate(&input, &blueprint, !has_flag("--no-color"))
+            );
+        }
+        if has_flag("--gears") {
+            println!("{}", render::gear_listing(&gears(&blueprint)));
+        }
+        return;
+    }
+
     println!("Solution Part 1: {}", solve_part1(&input));
     println!("Solution Part 2: {}", solve_part2(&input));
 }
@@ -39,41 +133,41 @@ fn solve_part2(input: &str) -> usize {
     gear_ratios(&blueprint).iter().sum()
 }
 
-fn number_has_adjacent_symbol(num: &Number, symbols: &Vec<Symbol>) -> bool {
-    symbols.iter().any(|sym| {
-        num.line.abs_diff(sym.line) <= 1 && num.start <= sym.column + 1 && num.end + 1 >= sym.column
-    })
+/// For every number, whether any symbol is adjacent to it.
+fn part_number_flags(blueprint: &Blueprint, index: &BlueprintIndex) -> Vec<bool> {
+    let mut is_adjacent = vec![false; blueprint.numbers.len()];
+    for sym in &blueprint.symbols {
+        for num in index.numbers_around(sym) {
+            is_adjacent[num] = true;
+        }
+    }
+    is_adjacent
 }
 
 fn adjacent_numbers(blueprint: &Blueprint) -> Vec<Number> {
+    let index = BlueprintIndex::new(blueprint);
+
     blueprint
         .numbers
         .iter()
-        .cloned()
-        .filter(|num| number_has_adjacent_symbol(num, &blueprint.symbols))
+        .zip(part_number_flags(blueprint, &index))
+        .filter(|(_, is_adjacent)| *is_adjacent)
+        .map(|(num, _)| num.clone())
         .collect()
 }
 
 fn gear_ratios(blueprint: &Blueprint) -> Vec<usize> {
-    let gears: Vec<_> = blueprint
+    let index = BlueprintIndex::new(blueprint);
+
+    blueprint
         .symbols
         .iter()
-        .cloned()
         .filter(|sym| sym.char == '*')
-        .collect();
-
-    gears
-        .iter()
         .map(|sym| {
-            let adjacent_numbers = blueprint
-                .numbers
-                .iter()
-                .filter(|num| number_has_adjacent_symbol(num, &vec![sym.clone()]))
-                .map(|num| num.value)
-                .collect_vec();
-
+            let adjacent_numbers = index.numbers_around(sym);
             if adjacent_numbers.len() == 2 {
-                adjacent_numbers[0] * adjacent_numbers[1]
+                blueprint.numbers[adjacent_numbers[0]].value
+                    * blueprint.numbers[adjacent_numbers[1]].value
             } else {
                 0
             }
@@ -81,6 +175,265 @@ fn gear_ratios(blueprint: &Blueprint) -> Vec<usize> {
         .collect_vec()
 }
 
+/// A `*` adjacent to exactly two numbers.
+#[derive(Debug, PartialEq)]
+struct Gear {
+    line: usize,
+    column: usize,
+    numbers: [usize; 2],
+    ratio: usize,
+}
+
+fn gears(blueprint: &Blueprint) -> Vec<Gear> {
+    let index = BlueprintIndex::new(blueprint);
+
+    blueprint
+        .symbols
+        .iter()
+        .filter(|sym| sym.char == '*')
+        .filter_map(|sym| match index.numbers_around(sym)[..] {
+            [first, second] => {
+                let numbers = [
+                    blueprint.numbers[first].value,
+                    blueprint.numbers[second].value,
+                ];
+                Some(Gear {
+                    line: sym.line,
+                    column: sym.column,
+                    numbers,
+                    ratio: numbers[0] * numbers[1],
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The solution before the index: every number is compared with every symbol.
+mod naive {
+    use super::*;
+
+    fn number_has_adjacent_symbol(num: &Number, sym: &Symbol) -> bool {
+        num.line.abs_diff(sym.line) <= 1 && num.start <= sym.column + 1 && num.end + 1 >= sym.column
+    }
+
+    pub(super) fn adjacent_numbers(blueprint: &Blueprint) -> Vec<Number> {
+        blueprint
+            .numbers
+            .iter()
+            .filter(|num| {
+                blueprint
+                    .symbols
+                    .iter()
+                    .any(|sym| number_has_adjacent_symbol(num, sym))
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub(super) fn gear_ratios(blueprint: &Blueprint) -> Vec<usize> {
+        blueprint
+            .symbols
+            .iter()
+            .filter(|sym| sym.char == '*')
+            .map(|sym| {
+                let adjacent_numbers = blueprint
+                    .numbers
+                    .iter()
+                    .filter(|num| number_has_adjacent_symbol(num, sym))
+                    .map(|num| num.value)
+                    .collect_vec();
+
+                if adjacent_numbers.len() == 2 {
+                    adjacent_numbers[0] * adjacent_numbers[1]
+                } else {
+                    0
+                }
+            })
+            .collect_vec()
+    }
+}
+
+/// A schematic of `size`×`size` cells, about a third of them covered by numbers and one in ten
+/// by symbols, from a xorshift generator seeded with `seed`.
+fn generate_schematic(size: usize, seed: u64) -> String {
+    let mut state = seed.max(1);
+    let mut random = move |bound: u64| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % bound
+    };
+
+    let mut schematic = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        let mut column = 0;
+        while column < size {
+            match random(10) {
+                0 => {
+                    schematic.push(b"*#+$/@%=-&"[random(10) as usize] as char);
+                    column += 1;
+                }
+                1..=3 => {
+                    let digits = (1 + random(3) as usize).min(size - column);
+                    for _ in 0..digits {
+                        schematic.push(char::from_digit(random(10) as u32, 10).unwrap());
+                    }
+                    column += digits;
+                    if column < size {
+                        schematic.push('.');
+                        column += 1;
+                    }
+                }
+                _ => {
+                    schematic.push('.');
+                    column += 1;
+                }
+            }
+        }
+        schematic.push('\n');
+    }
+    schematic
+}
+
+fn bench(size: usize, naive: bool) {
+    let schematic = generate_schematic(size, 2023);
+
+    let start = Instant::now();
+    let blueprint = parser::parse(&schematic);
+    println!(
+        "Parsed {} numbers and {} symbols in {:?}",
+        blueprint.numbers.len(),
+        blueprint.symbols.len(),
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let part1: usize = adjacent_numbers(&blueprint).iter().map(|n| n.value).sum();
+    let part2: usize = gear_ratios(&blueprint).iter().sum();
+    println!("Indexed: {part1} and {part2} in {:?}", start.elapsed());
+
+    if naive {
+        let start = Instant::now();
+        let part1: usize = naive::adjacent_numbers(&blueprint)
+            .iter()
+            .map(|n| n.value)
+            .sum();
+        let part2: usize = naive::gear_ratios(&blueprint).iter().sum();
+        println!("Naive: {part1} and {part2} in {:?}", start.elapsed());
+    }
+}
+
+mod render {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Highlight {
+        PartNumber,
+        OtherNumber,
+        Gear,
+        OtherStar,
+    }
+
+    impl Highlight {
+        fn ansi_colour(&self) -> &str {
+            match self {
+                Highlight::PartNumber => "\x1b[32m",
+                Highlight::OtherNumber => "\x1b[31m",
+                Highlight::Gear => "\x1b[1;33m",
+                Highlight::OtherStar => "\x1b[35m",
+            }
+        }
+
+        fn brackets(&self) -> (char, char) {
+            match self {
+                Highlight::PartNumber => ('[', ']'),
+                Highlight::OtherNumber => ('(', ')'),
+                Highlight::Gear => ('{', '}'),
+                Highlight::OtherStar => ('<', '>'),
+            }
+        }
+    }
+
+    /// Reprints the schematic with its numbers and `*` symbols highlighted. With `ansi` they are
+    /// coloured, part numbers green, other numbers red, gears yellow and other `*` magenta.
+    /// Otherwise they are put in brackets: `[467]` for part numbers, `(114)` for other numbers,
+    /// `{*}` for gears and `<*>` for other `*`.
+    pub(super) fn annotate(input: &str, blueprint: &Blueprint, ansi: bool) -> String {
+        let index = BlueprintIndex::new(blueprint);
+        let gear_positions: HashSet<_> = gears(blueprint)
+            .iter()
+            .map(|gear| (gear.line, gear.column))
+            .collect();
+
+        // The highlighted columns of every line as (start, end, highlight), ordered by start
+        let mut highlights = vec![Vec::new(); input.lines().count()];
+        for (num, is_part) in blueprint
+            .numbers
+            .iter()
+            .zip(part_number_flags(blueprint, &index))
+        {
+            let highlight = match is_part {
+                true => Highlight::PartNumber,
+                false => Highlight::OtherNumber,
+            };
+            highlights[num.line].push((num.start, num.end, highlight));
+        }
+        for sym in blueprint.symbols.iter().filter(|sym| sym.char == '*') {
+            let highlight = match gear_positions.contains(&(sym.line, sym.column)) {
+                true => Highlight::Gear,
+                false => Highlight::OtherStar,
+            };
+            highlights[sym.line].push((sym.column, sym.column, highlight));
+        }
+
+        input
+            .lines()
+            .zip(&mut highlights)
+            .map(|(line, line_highlights)| {
+                line_highlights.sort_by_key(|(start, _, _)| *start);
+
+                let mut annotated = String::new();
+                let mut column = 0;
+                for (start, end, highlight) in line_highlights.iter() {
+                    annotated.push_str(&line[column..*start]);
+                    let text = &line[*start..=*end];
+                    if ansi {
+                        annotated.push_str(&format!("{}{text}\x1b[0m", highlight.ansi_colour()));
+                    } else {
+                        let (open, close) = highlight.brackets();
+                        annotated.push_str(&format!("{open}{text}{close}"));
+                    }
+                    column = end + 1;
+                }
+                annotated.push_str(&line[column..]);
+                annotated
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// One line per gear with its position, 1-based, its two numbers and its ratio.
+    pub(super) fn gear_listing(gears: &[Gear]) -> String {
+        gears
+            .iter()
+            .map(|gear| {
+                format!(
+                    "Gear at line {}, column {}: {} * {} = {}",
+                    gear.line + 1,
+                    gear.column + 1,
+                    gear.numbers[0],
+                    gear.numbers[1],
+                    gear.ratio
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
 mod parser {
     use regex::Regex;
 
@@ -148,7 +501,7 @@ mod tests {
 ...$.*....
 .664.598..
 "#;
-        let solution = solve_part1(&input);
+        let solution = solve_part1(input);
         assert_eq!(solution, 4361);
     }
 
@@ -165,7 +518,64 @@ mod tests {
 ...$.*....
 .664.598..
 "#;
-        let solution = solve_part2(&input);
+        let solution = solve_part2(input);
         assert_eq!(solution, 467835);
     }
+
+    #[test]
+    fn index_matches_naive_solution() {
+        for seed in 1..20 {
+            let schematic = generate_schematic(40, seed);
+            let blueprint = parser::parse(&schematic);
+
+            let values = |numbers: Vec<Number>| numbers.iter().map(|n| n.value).collect_vec();
+            assert_eq!(
+                values(adjacent_numbers(&blueprint)),
+                values(naive::adjacent_numbers(&blueprint))
+            );
+            assert_eq!(gear_ratios(&blueprint), naive::gear_ratios(&blueprint));
+        }
+    }
+
+    #[test]
+    fn numbers_around_works() {
+        let blueprint = parser::parse("12.\n.*.\n..345");
+        let index = BlueprintIndex::new(&blueprint);
+
+        let gear = &blueprint.symbols[0];
+        assert_eq!(index.numbers_around(gear), vec![0, 1]);
+        assert_eq!(gear_ratios(&blueprint), vec![12 * 345]);
+    }
+
+    #[test]
+    fn annotate_works() {
+        let input = "467..114..\n...*......\n..35..633.\n......#...\n617*......";
+        let blueprint = parser::parse(input);
+
+        assert_eq!(
+            render::annotate(input, &blueprint, false),
+            "[467]..(114)..\n...{*}......\n..[35]..[633].\n......#...\n[617]<*>......"
+        );
+        assert!(render::annotate(input, &blueprint, true).contains("\x1b[32m467\x1b[0m"));
+    }
+
+    #[test]
+    fn gears_work() {
+        let input = "467..114..\n...*......\n..35..633.\n......#...\n617*......";
+        let gears = gears(&parser::parse(input));
+
+        assert_eq!(
+            gears,
+            vec![Gear {
+                line: 1,
+                column: 3,
+                numbers: [467, 35],
+                ratio: 16345
+            }]
+        );
+        assert_eq!(
+            render::gear_listing(&gears),
+            "Gear at line 2, column 4: 467 * 35 = 16345"
+        );
+    }
 }
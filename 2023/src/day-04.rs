@@ -1,61 +1,71 @@
+use std::collections::HashSet;
 use std::fs::read_to_string;
 
 struct Card {
+    id: usize,
     winning_numbers: Vec<usize>,
     listed_numbers: Vec<usize>,
-    copies: usize,
+}
+
+/// Two cards share the ID, so it's ambiguous which of them a won copy is of.
+#[derive(Debug, PartialEq)]
+struct DuplicateCardId {
+    id: usize,
 }
 
 fn main() {
     let input = read_to_string("./inputs/day-04.txt").expect("read input");
     println!("Solution Part 1: {}", solve_part1(&input));
-    println!("Solution Part 2: {}", solve_part2(&input));
+    println!("Solution Part 2: {}", solve_part2(&input).unwrap());
 }
 
 fn solve_part1(input: &str) -> usize {
     let cards = parser::parse(input);
-    cards.iter().map(|card| card_value(card)).sum()
+    cards.iter().map(card_value).sum()
 }
 
-fn solve_part2(input: &str) -> usize {
-    let mut cards = parser::parse(input);
-    update_card_copies(&mut cards);
-
-    cards.iter().map(|card| card.copies).sum()
+fn solve_part2(input: &str) -> Result<usize, DuplicateCardId> {
+    let cards = parser::parse(input);
+    Ok(copy_counts(&cards)?.iter().map(|(_, copies)| copies).sum())
 }
 
-fn update_card_copies(cards: &mut Vec<Card>) {
-    for i in 0..cards.len() {
-        let matching_numbers_count = matching_numbers_count(&cards[i]);
-        increment_card_copies(cards, i, matching_numbers_count);
+/// How many copies of every card are won, as `(card ID, copies)` ordered by ID, the original card
+/// included. A card with `n` matching numbers wins one copy of each of the next `n` cards by ID
+/// per copy of itself, cards past the end of the table excepted.
+///
+/// The cards are sorted by ID, and the copies a card wins are added to a running count right away
+/// and recorded to be taken off again at the position of the first card past the ones won. Only
+/// finding that position depends on the IDs, so gaps between them cost nothing.
+fn copy_counts(cards: &[Card]) -> Result<Vec<(usize, usize)>, DuplicateCardId> {
+    let mut sorted: Vec<_> = cards.iter().collect();
+    sorted.sort_by_key(|card| card.id);
+    if let Some(pair) = sorted.windows(2).find(|pair| pair[0].id == pair[1].id) {
+        return Err(DuplicateCardId { id: pair[0].id });
     }
-}
 
-fn increment_card_copies(cards: &mut Vec<Card>, start_exclusive: usize, count: usize) {
-    if count == 0 || cards.len() == start_exclusive + 1 {
-        return;
+    let mut expiring = vec![0; sorted.len() + 1];
+    let mut won = 0;
+    let mut copies = Vec::with_capacity(sorted.len());
+    for (position, card) in sorted.iter().enumerate() {
+        won -= expiring[position];
+
+        let card_copies = 1 + won;
+        won += card_copies;
+        let last_won_id = card.id.saturating_add(matching_numbers_count(card));
+        let expires_at = sorted.partition_point(|other| other.id <= last_won_id);
+        expiring[expires_at] += card_copies;
+        copies.push((card.id, card_copies));
     }
 
-    let increment = cards[start_exclusive].copies;
-
-    let start = std::cmp::min(cards.len() - 1, start_exclusive + 1);
-    let end = std::cmp::min(cards.len(), start + count);
-
-    cards[start..end]
-        .iter_mut()
-        .for_each(|card| card.copies += increment);
+    Ok(copies)
 }
 
+/// The numbers that are both winning and listed, each counted once.
 fn matching_numbers_count(card: &Card) -> usize {
-    let both_numbers = {
-        let mut n = card.listed_numbers.clone();
-        n.extend(card.winning_numbers.iter());
-        n.sort();
-        n.dedup();
-        n
-    };
-
-    (card.winning_numbers.len() + card.listed_numbers.len()) - both_numbers.len()
+    let winning: HashSet<_> = card.winning_numbers.iter().collect();
+    let listed: HashSet<_> = card.listed_numbers.iter().collect();
+
+    winning.intersection(&listed).count()
 }
 
 fn card_value(card: &Card) -> usize {
@@ -71,16 +81,17 @@ mod parser {
     use super::*;
 
     pub(super) fn parse(input: &str) -> Vec<Card> {
-        input.lines().map(|line| parse_card(line)).collect()
+        input.lines().map(parse_card).collect()
     }
 
     fn parse_card(line: &str) -> Card {
-        let (_, numbers_str) = line.split_once(": ").unwrap();
+        let (card_str, numbers_str) = line.split_once(':').unwrap();
+        let id = card_str.strip_prefix("Card").unwrap().trim();
         let (winning_nums_str, listed_nums_str) = numbers_str.split_once(" | ").unwrap();
         Card {
+            id: id.parse().unwrap(),
             winning_numbers: parse_numbers(winning_nums_str),
             listed_numbers: parse_numbers(listed_nums_str),
-            copies: 1,
         }
     }
 
@@ -94,8 +105,6 @@ mod parser {
 
 #[cfg(test)]
 mod tests {
-    use itertools::Itertools;
-
     use super::*;
 
     #[test]
@@ -107,7 +116,7 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 "#;
-        let solution = solve_part1(&input);
+        let solution = solve_part1(input);
         assert_eq!(solution, 13);
     }
 
@@ -120,46 +129,66 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 "#;
-        let solution = solve_part2(&input);
-        assert_eq!(solution, 30);
+        let solution = solve_part2(input);
+        assert_eq!(solution, Ok(30));
     }
 
     #[test]
-    fn increment_card_copies_works() {
-        fn run_test(start_exclusive: usize, count: usize) -> Vec<usize> {
-            let mut cards = vec![
-                Card {
-                    listed_numbers: vec![],
-                    winning_numbers: vec![],
-                    copies: 1,
-                },
-                Card {
-                    listed_numbers: vec![],
-                    winning_numbers: vec![],
-                    copies: 1,
-                },
-                Card {
-                    listed_numbers: vec![],
-                    winning_numbers: vec![],
-                    copies: 1,
-                },
-            ];
-
-            increment_card_copies(&mut cards, start_exclusive, count);
-
-            cards.iter().map(|c| c.copies).collect_vec()
+    fn copy_counts_works() {
+        fn run_test(matches: &[usize]) -> Vec<usize> {
+            let cards: Vec<_> = matches
+                .iter()
+                .enumerate()
+                .map(|(index, count)| Card {
+                    id: index + 1,
+                    winning_numbers: (0..*count).collect(),
+                    listed_numbers: (0..*count).collect(),
+                })
+                .collect();
+
+            copy_counts(&cards)
+                .unwrap()
+                .iter()
+                .map(|(_, copies)| *copies)
+                .collect()
         }
 
-        assert_eq!(run_test(0, 0), vec![1, 1, 1]);
-        assert_eq!(run_test(1, 0), vec![1, 1, 1]);
-        assert_eq!(run_test(2, 0), vec![1, 1, 1]);
+        assert_eq!(run_test(&[0, 0, 0]), vec![1, 1, 1]);
+        assert_eq!(run_test(&[1, 0, 0]), vec![1, 2, 1]);
+        assert_eq!(run_test(&[0, 1, 0]), vec![1, 1, 2]);
+        assert_eq!(run_test(&[0, 0, 1]), vec![1, 1, 1]);
+        assert_eq!(run_test(&[2, 0, 0]), vec![1, 2, 2]);
+        assert_eq!(run_test(&[1, 1, 0]), vec![1, 2, 3]);
+        assert_eq!(run_test(&[0, 2, 2]), vec![1, 1, 2]);
+        assert_eq!(run_test(&[4, 2, 2, 1, 0, 0]), vec![1, 2, 4, 8, 14, 1]);
+    }
+
+    #[test]
+    fn card_ids_are_parsed() {
+        let input = "Card   7: 1 2 | 2 3\nCard 3: 5 | 5\nCard 4: 1 | 2";
+        let cards = parser::parse(input);
+        assert_eq!(
+            cards.iter().map(|c| c.id).collect::<Vec<_>>(),
+            vec![7, 3, 4]
+        );
+
+        // Card 3 wins a copy of card 4, card 7 one of card 8 which doesn't exist
+        assert_eq!(copy_counts(&cards), Ok(vec![(3, 1), (4, 2), (7, 1)]));
+
+        // Far apart IDs only take space for the cards there are
+        let cards = parser::parse("Card 4000000000: 1 | 1\nCard 1: 1 | 1");
+        assert_eq!(copy_counts(&cards), Ok(vec![(1, 1), (4000000000, 1)]));
+    }
 
-        assert_eq!(run_test(0, 1), vec![1, 2, 1]);
-        assert_eq!(run_test(1, 1), vec![1, 1, 2]);
-        assert_eq!(run_test(2, 1), vec![1, 1, 1]);
+    #[test]
+    fn duplicate_card_ids_fail() {
+        let cards = parser::parse("Card 1: 1 | 1\nCard 2: 2 | 3\nCard 1: 2 | 3");
+        assert_eq!(copy_counts(&cards), Err(DuplicateCardId { id: 1 }));
+    }
 
-        assert_eq!(run_test(0, 2), vec![1, 2, 2]);
-        assert_eq!(run_test(1, 2), vec![1, 1, 2]);
-        assert_eq!(run_test(2, 2), vec![1, 1, 1]);
+    #[test]
+    fn duplicate_numbers_are_matched_once() {
+        let card = parser::parse("Card 1: 5 5 6 | 5 7 5").remove(0);
+        assert_eq!(matching_numbers_count(&card), 1);
     }
 }